@@ -0,0 +1,64 @@
+//! Generates the table of solutions that is compiled into the `registry` binary.
+//! See `src/template/registry.rs` for how it is used.
+use std::{env, fs, path::Path, process::Command};
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/bin");
+
+    // expose the compiler and target, which are recorded with the timings.
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    if let Ok(output) = Command::new(rustc).arg("--version").output() {
+        let version = String::from_utf8_lossy(&output.stdout);
+        println!("cargo:rustc-env=AOC_RUSTC_VERSION={}", version.trim());
+    }
+    println!("cargo:rustc-env=AOC_TARGET={}", env::var("TARGET").unwrap());
+
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let bin_dir = Path::new(&manifest_dir).join("src").join("bin");
+
+    // solutions are named `05.rs`, or `2024_05.rs` when namespaced by year.
+    let mut solutions: Vec<(Option<u16>, u8)> = fs::read_dir(&bin_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| {
+                    let name = entry.ok()?.file_name().into_string().ok()?;
+                    let stem = name.strip_suffix(".rs")?;
+                    let (year, day) = match stem.split_once('_') {
+                        Some((year, day)) if year.len() == 4 => (Some(year.parse().ok()?), day),
+                        Some(_) => return None,
+                        None => (None, stem),
+                    };
+                    let number: u8 = day.parse().ok()?;
+                    (day.len() == 2 && (1..=25).contains(&number)).then_some((year, number))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    solutions.sort_unstable();
+
+    let mut modules = String::new();
+    let mut entries = String::new();
+
+    for (year, day) in solutions {
+        let name = match year {
+            Some(year) => format!("{year}_{day:02}"),
+            None => format!("{day:02}"),
+        };
+        let path = bin_dir.join(format!("{name}.rs"));
+        // NOTE: solutions are linted and tested as their own binaries already.
+        modules.push_str(&format!(
+            "#[allow(dead_code, unused, clippy::all)]\n#[path = {:?}]\nmod day_{name};\n\n",
+            path.display().to_string()
+        ));
+        entries.push_str(&format!(
+            "    advent_of_code::template::registry::Solution {{ year: {year:?}, day: advent_of_code::day!({day}), run: day_{name}::__solve }},\n"
+        ));
+    }
+
+    let registry = format!(
+        "{modules}/// Every solution in `src/bin`, ordered by year and day.\nstatic SOLUTIONS: &[advent_of_code::template::registry::Solution] = &[\n{entries}];\n"
+    );
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("registry.rs");
+    fs::write(out_path, registry).unwrap();
+}
@@ -1,26 +1,22 @@
 use std::collections::{HashMap, HashSet};
 use advent_of_code::helpers::Point;
 
-advent_of_code::solution!(8);
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let (size, antennas) = process_input(input);
+advent_of_code::solution!(8, parse: process_input);
 
+pub fn part_one((size, antennas): &(Point, HashMap<char, Vec<Point>>)) -> Option<u32> {
     // Calculate interference
     let mut anti_nodes: HashSet<Point> = HashSet::new();
     for (_, value) in antennas.iter() {
-        calculate_anti_nodes(value, &mut anti_nodes, &size);
+        calculate_anti_nodes(value, &mut anti_nodes, size);
     }
     Some(anti_nodes.len() as u32)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (size, antennas) = process_input(input);
-
+pub fn part_two((size, antennas): &(Point, HashMap<char, Vec<Point>>)) -> Option<u32> {
     // Calculate interference
     let mut anti_nodes: HashSet<Point> = HashSet::new();
     for (_, value) in antennas.iter() {
-        calculate_anti_nodes_part2(value, &mut anti_nodes, &size);
+        calculate_anti_nodes_part2(value, &mut anti_nodes, size);
     }
     Some(anti_nodes.len() as u32)
 }
@@ -97,13 +93,15 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let input = process_input(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&input);
         assert_eq!(result, Some(14));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = process_input(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&input);
         assert_eq!(result, Some(34));
     }
 }
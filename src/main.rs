@@ -1,4 +1,4 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{all, download, read, readme, scaffold, solve, time};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
@@ -7,16 +7,21 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::{
+        parse_duration, select_year, BenchConfig, Day, Export, MergeStrategy, RunOptions,
+    };
     use std::process;
+    use std::time::Duration;
 
     pub enum AppArguments {
         Download {
             day: Day,
+            force: bool,
         },
         Read {
             day: Day,
         },
+        Readme,
         Scaffold {
             day: Day,
             download: bool,
@@ -26,43 +31,109 @@ mod args {
             day: Day,
             release: bool,
             dhat: bool,
+            memory: bool,
             submit: Option<u8>,
+            force: bool,
+            bench: Option<BenchConfig>,
+            record: bool,
+            timeout: Option<Duration>,
         },
         All {
-            release: bool,
+            options: RunOptions,
+        },
+        TimeHistory {
+            day: Day,
         },
         Time {
             all: bool,
             day: Option<Day>,
             store: bool,
+            merge: MergeStrategy,
+            compare: bool,
+            fail_above: Option<f64>,
+            export: Option<Export>,
+            options: RunOptions,
         },
         #[cfg(feature = "today")]
         Today,
     }
 
+    /// Parse the `--bench-time`, `--min-samples` and `--max-samples` options.
+    fn parse_bench(
+        args: &mut pico_args::Arguments,
+    ) -> Result<Option<BenchConfig>, Box<dyn std::error::Error>> {
+        Ok(BenchConfig::from_options(
+            args.opt_value_from_fn("--bench-time", parse_duration)?,
+            args.opt_value_from_str("--min-samples")?,
+            args.opt_value_from_str("--max-samples")?,
+        ))
+    }
+
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
 
+        // every command works on the data and solutions of the year passed via `--year`.
+        if let Some(year) = args.opt_value_from_str("--year")? {
+            select_year(year);
+        }
+
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
-                release: args.contains("--release"),
+                options: RunOptions {
+                    is_release: args.contains("--release"),
+                    bench: parse_bench(&mut args)?,
+                    is_isolated: args.contains("--isolated"),
+                    is_record: args.contains("--record"),
+                    timeout: args.opt_value_from_fn("--timeout", parse_duration)?,
+                    is_count_alloc: args.contains("--memory"),
+                },
+            },
+            Some("time") if args.contains("--history") => AppArguments::TimeHistory {
+                day: args.free_from_str()?,
             },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let merge = args.opt_value_from_str("--merge")?.unwrap_or_default();
+                let compare = args.contains("--compare");
+                let fail_above = args.opt_value_from_str("--fail-above")?;
+                let export = args
+                    .opt_value_from_str("--export")?
+                    .map(|format| -> Result<Export, pico_args::Error> {
+                        Ok(Export {
+                            format,
+                            out: args.opt_value_from_str("--out")?,
+                        })
+                    })
+                    .transpose()?;
+                let options = RunOptions {
+                    is_release: true,
+                    bench: Some(parse_bench(&mut args)?.unwrap_or_default()),
+                    is_isolated: args.contains("--isolated"),
+                    is_record: false,
+                    timeout: args.opt_value_from_fn("--timeout", parse_duration)?,
+                    is_count_alloc: args.contains("--memory"),
+                };
 
                 AppArguments::Time {
                     all,
                     day: args.opt_free_from_str()?,
                     store,
+                    merge,
+                    compare,
+                    fail_above,
+                    export,
+                    options,
                 }
             }
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
+                force: args.contains("--force"),
             },
             Some("read") => AppArguments::Read {
                 day: args.free_from_str()?,
             },
+            Some("readme") => AppArguments::Readme,
             Some("scaffold") => AppArguments::Scaffold {
                 day: args.free_from_str()?,
                 download: args.contains("--download"),
@@ -72,7 +143,12 @@ mod args {
                 day: args.free_from_str()?,
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
+                force: args.contains("--force"),
                 dhat: args.contains("--dhat"),
+                memory: args.contains("--memory"),
+                bench: parse_bench(&mut args)?,
+                record: args.contains("--record"),
+                timeout: args.opt_value_from_fn("--timeout", parse_duration)?,
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -102,10 +178,23 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
+            AppArguments::All { options } => all::handle(&options),
+            AppArguments::Time {
+                day,
+                all,
+                store,
+                merge,
+                compare,
+                fail_above,
+                export,
+                options,
+            } => time::handle(
+                day, all, store, merge, compare, fail_above, export, &options,
+            ),
+            AppArguments::TimeHistory { day } => time::handle_history(day),
+            AppArguments::Download { day, force } => download::handle(day, force),
             AppArguments::Read { day } => read::handle(day),
+            AppArguments::Readme => readme::handle(),
             AppArguments::Scaffold {
                 day,
                 download,
@@ -113,21 +202,28 @@ fn main() {
             } => {
                 scaffold::handle(day, overwrite);
                 if download {
-                    download::handle(day);
+                    download::handle(day, false);
                 }
             }
             AppArguments::Solve {
                 day,
                 release,
                 dhat,
+                memory,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                force,
+                bench,
+                record,
+                timeout,
+            } => solve::handle(
+                day, release, dhat, memory, submit, force, bench, record, timeout,
+            ),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
                     Some(day) => {
                         scaffold::handle(day, false);
-                        download::handle(day);
+                        download::handle(day, false);
                         read::handle(day)
                     }
                     None => {
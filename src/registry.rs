@@ -0,0 +1,7 @@
+//! Runs every solution in a single process, see `template::registry`.
+
+include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+
+fn main() {
+    advent_of_code::template::registry::run(SOLUTIONS);
+}
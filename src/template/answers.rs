@@ -0,0 +1,263 @@
+/// Known answers that solutions are verified against.
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Error, ErrorKind},
+    str::FromStr,
+};
+use tinyjson::JsonValue;
+
+use crate::template::json;
+use crate::template::paths::paths;
+use crate::template::Day;
+
+/// Represents the confirmed answers for a single day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Answer {
+    pub day: Day,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+/// Represents the confirmed answers for a set of days.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Answers {
+    pub data: Vec<Answer>,
+}
+
+/// Result of verifying an answer against the stored answers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Check {
+    Correct,
+    /// The answer differs from the stored answer, which is attached.
+    Incorrect(String),
+    /// There is no stored answer to verify against.
+    Unknown,
+}
+
+impl Answers {
+    /// Dehydrate answers to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        fs::write(paths().answers(), json::format_sorted(&json))
+    }
+
+    /// Rehydrate answers from a JSON file. If not present, returns empty answers.
+    /// Fails if the file can not be read or parsed.
+    pub fn read_from_file() -> Result<Self, String> {
+        let path = paths().answers();
+        let answers = match fs::read_to_string(&path) {
+            Ok(s) => Answers::try_from(s),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Answers::default()),
+            Err(e) => Err(e.to_string()),
+        };
+        answers.map_err(|e| format!("Failed to read answers from \"{}\": {e}", path.display()))
+    }
+
+    /// Get the stored answer for a part of a day.
+    pub fn get(&self, day: Day, part: u8) -> Option<&str> {
+        let answer = self.data.iter().find(|a| a.day == day)?;
+        match part {
+            1 => answer.part_1.as_deref(),
+            2 => answer.part_2.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Store an answer for a part of a day, replacing a previous answer if present.
+    pub fn set(&mut self, day: Day, part: u8, value: &str) {
+        let index = match self.data.iter().position(|a| a.day == day) {
+            Some(index) => index,
+            None => {
+                self.data.push(Answer {
+                    day,
+                    part_1: None,
+                    part_2: None,
+                });
+                self.data.sort_unstable_by_key(|a| a.day);
+                self.data.iter().position(|a| a.day == day).unwrap()
+            }
+        };
+
+        match part {
+            1 => self.data[index].part_1 = Some(value.into()),
+            2 => self.data[index].part_2 = Some(value.into()),
+            _ => {}
+        }
+    }
+
+    /// Verify an answer of a part against the stored answer.
+    /// A missing answer is incorrect if an answer is stored.
+    pub fn check(&self, day: Day, part: u8, value: Option<&str>) -> Check {
+        match (self.get(day, part), value) {
+            (None, _) => Check::Unknown,
+            (Some(expected), Some(value)) if expected == value => Check::Correct,
+            (Some(expected), _) => Check::Incorrect(expected.into()),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Answers> for JsonValue {
+    fn from(value: Answers) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Answers {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Answers {
+            data: json_data
+                .iter()
+                .map(Answer::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Answer> for JsonValue {
+    fn from(value: &Answer) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+
+        for (key, part) in [("part_1", &value.part_1), ("part_2", &value.part_2)] {
+            map.insert(
+                key.into(),
+                match part {
+                    Some(x) => JsonValue::String(x.clone()),
+                    None => JsonValue::Null,
+                },
+            );
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Answer {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected answer to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected answer.day to be a Day struct.")?;
+
+        let part_1 = json
+            .get("part_1")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected answer.part_1 to be null or string.")?;
+
+        let part_2 = json
+            .get("part_2")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected answer.part_2 to be null or string.")?;
+
+        Ok(Answer {
+            day,
+            part_1: part_1.cloned(),
+            part_2: part_2.cloned(),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use crate::day;
+
+    use super::{Answer, Answers, Check};
+
+    fn get_mock_answers() -> Answers {
+        Answers {
+            data: vec![
+                Answer {
+                    day: day!(1),
+                    part_1: Some("11".into()),
+                    part_2: Some("31".into()),
+                },
+                Answer {
+                    day: day!(3),
+                    part_1: Some("#..#\n.##.".into()),
+                    part_2: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn roundtrips_answers() {
+        let answers = get_mock_answers();
+        let json = tinyjson::JsonValue::from(answers.clone())
+            .stringify()
+            .unwrap();
+        let parsed = Answers::try_from(json).unwrap();
+        assert_eq!(parsed.data, answers.data);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_invalid_json() {
+        Answers::try_from(r#"{}"#.to_string()).unwrap();
+    }
+
+    #[test]
+    fn checks_answers() {
+        let answers = get_mock_answers();
+        assert_eq!(answers.check(day!(1), 1, Some("11")), Check::Correct);
+        assert_eq!(
+            answers.check(day!(1), 2, Some("30")),
+            Check::Incorrect("31".into())
+        );
+        assert_eq!(
+            answers.check(day!(1), 2, None),
+            Check::Incorrect("31".into())
+        );
+        assert_eq!(answers.check(day!(3), 2, Some("1")), Check::Unknown);
+        assert_eq!(answers.check(day!(2), 1, Some("1")), Check::Unknown);
+    }
+
+    #[test]
+    fn sets_answers() {
+        let mut answers = get_mock_answers();
+        answers.set(day!(3), 2, "42");
+        answers.set(day!(2), 1, "7");
+        answers.set(day!(1), 1, "12");
+
+        assert_eq!(answers.data.len(), 3);
+        assert_eq!(answers.data[1].day, day!(2));
+        assert_eq!(answers.get(day!(2), 1), Some("7"));
+        assert_eq!(answers.get(day!(3), 2), Some("42"));
+        assert_eq!(answers.get(day!(1), 1), Some("12"));
+    }
+}
@@ -0,0 +1,402 @@
+/// Client for the Advent of Code website, authenticated with the session cookie of a user.
+use std::{
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::template::html;
+use crate::template::inputs::{self, InputHashes, InvalidInput};
+use crate::template::paths::{paths, selected_year};
+use crate::template::Day;
+
+static DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Identifies the template to the Advent of Code servers, as requested by its author.
+static USER_AGENT: &str = "github.com/fspoettel/advent-of-code-rust";
+
+#[derive(Debug)]
+pub enum AocClientError {
+    SessionNotFound,
+    YearNotSet,
+    /// The server responded with an error status.
+    Status(u16),
+    /// The server responded with something other than a puzzle input.
+    InvalidInput(InvalidInput),
+    /// The server could not be reached.
+    Transport(String),
+    IO(io::Error),
+}
+
+impl Display for AocClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocClientError::SessionNotFound => write!(
+                f,
+                "no session cookie found. Set `AOC_SESSION` or paste it into `~/.adventofcode.session`."
+            ),
+            AocClientError::YearNotSet => {
+                write!(f, "no year set. Set `AOC_YEAR` or pass `--year`.")
+            }
+            AocClientError::Status(status) => {
+                write!(f, "the server responded with status {status}.")
+            }
+            AocClientError::InvalidInput(e) => write!(f, "{e}"),
+            AocClientError::Transport(e) => write!(f, "the server could not be reached: {e}"),
+            AocClientError::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<io::Error> for AocClientError {
+    fn from(e: io::Error) -> Self {
+        AocClientError::IO(e)
+    }
+}
+
+impl From<ureq::Error> for AocClientError {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Status(status, _) => AocClientError::Status(status),
+            ureq::Error::Transport(e) => AocClientError::Transport(e.to_string()),
+        }
+    }
+}
+
+pub struct Client {
+    base_url: String,
+    session: String,
+    year: u16,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str, year: u16) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').into(),
+            session: session.into(),
+            year,
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    /// A client for the selected year, with the session cookie of the user.
+    /// The base URL can be changed with `AOC_BASE_URL`, e.g. to test against a local server.
+    pub fn from_env() -> Result<Self, AocClientError> {
+        let year = get_year().ok_or(AocClientError::YearNotSet)?;
+        let session = get_session().ok_or(AocClientError::SessionNotFound)?;
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.into());
+        Ok(Self::new(&base_url, &session, year))
+    }
+
+    fn url(&self, day: Day, path: &str) -> String {
+        format!(
+            "{}/{}/day/{}{path}",
+            self.base_url,
+            self.year,
+            day.into_inner()
+        )
+    }
+
+    fn get(&self, url: &str) -> Result<String, AocClientError> {
+        let response = match self
+            .agent
+            .get(url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()
+        {
+            // error pages explain an expired session or a locked puzzle better than their status.
+            Err(ureq::Error::Status(status, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                return Err(match inputs::validate(&body) {
+                    Err(e @ (InvalidInput::LoginPage | InvalidInput::Locked)) => {
+                        AocClientError::InvalidInput(e)
+                    }
+                    _ => AocClientError::Status(status),
+                });
+            }
+            response => response?,
+        };
+        Ok(response.into_string()?)
+    }
+
+    /// The puzzle input of the user. Responses that are not a puzzle input are an error.
+    pub fn input(&self, day: Day) -> Result<String, AocClientError> {
+        let input = self.get(&self.url(day, "/input"))?;
+        inputs::validate(&input).map_err(AocClientError::InvalidInput)?;
+        Ok(input)
+    }
+
+    /// The description of the puzzle as Markdown. Contains part two once part one is solved.
+    pub fn puzzle(&self, day: Day) -> Result<String, AocClientError> {
+        let page = self.get(&self.url(day, ""))?;
+        let articles: Vec<String> = html::articles(&page)
+            .into_iter()
+            .map(html::to_markdown)
+            .collect();
+        Ok(articles.join("\n"))
+    }
+
+    /// Submit an answer. Returns the message of the response.
+    pub fn submit(&self, day: Day, part: u8, answer: &str) -> Result<String, AocClientError> {
+        let response = self
+            .agent
+            .post(&self.url(day, "/answer"))
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])?;
+
+        let page = response.into_string()?;
+        let message = html::articles(&page)
+            .into_iter()
+            .map(html::to_text)
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(message)
+    }
+}
+
+/// The session cookie, read from `AOC_SESSION` or the `.adventofcode.session` file used by aoc-cli.
+fn get_session() -> Option<String> {
+    if let Some(session) = env::var("AOC_SESSION")
+        .ok()
+        .filter(|x| !x.trim().is_empty())
+    {
+        return Some(session.trim().into());
+    }
+
+    let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?;
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(&home).join(".config"));
+
+    [
+        PathBuf::from(&home).join(".adventofcode.session"),
+        config_dir.join("adventofcode.session"),
+    ]
+    .iter()
+    .find_map(|path| fs::read_to_string(path).ok())
+    .map(|x| x.trim().to_string())
+    .filter(|x| !x.is_empty())
+}
+
+/// The year passed via `--year`, or the year set in `AOC_YEAR`.
+pub fn get_year() -> Option<u16> {
+    if let Some(year) = selected_year() {
+        return Some(year);
+    }
+
+    match std::env::var("AOC_YEAR") {
+        Ok(x) => x.parse().ok().or(None),
+        Err(_) => None,
+    }
+}
+
+/// Write the puzzle description of a day and print it.
+pub fn read(day: Day) -> Result<(), AocClientError> {
+    let puzzle = Client::from_env()?.puzzle(day)?;
+    write_file(&paths().puzzle(day), &puzzle)?;
+    println!("{puzzle}");
+    Ok(())
+}
+
+/// Write the puzzle input and description of a day.
+/// An existing input is only downloaded again with `force`, and a warning is printed if it changed.
+pub fn download(day: Day, force: bool) -> Result<(), AocClientError> {
+    let client = Client::from_env()?;
+    let input_path = paths().input(day);
+    let puzzle_path = paths().puzzle(day);
+    let mut hashes = InputHashes::read_from_file();
+
+    let existing = fs::read_to_string(&input_path)
+        .ok()
+        .filter(|x| !x.trim().is_empty());
+
+    match existing {
+        Some(existing) if !force => {
+            println!(
+                "🎄 Input \"{}\" exists, pass `--force` to download it again.",
+                input_path.display()
+            );
+            if hashes.has_changed(day, &existing) {
+                eprintln!("Warning: the input of day {day} was changed since it was downloaded.");
+            }
+        }
+        _ => {
+            let input = client.input(day)?;
+            if hashes.has_changed(day, &input) {
+                eprintln!(
+                    "Warning: the input of day {day} differs from the one downloaded before."
+                );
+            }
+
+            write_file(&input_path, &input)?;
+            hashes.set(day, &input);
+            if let Err(e) = hashes.store_file() {
+                eprintln!("Failed to store the hash of the input: {e}");
+            }
+            println!(
+                "🎄 Successfully wrote input to \"{}\".",
+                input_path.display()
+            );
+        }
+    }
+
+    write_file(&puzzle_path, &client.puzzle(day)?)?;
+    println!(
+        "🎄 Successfully wrote puzzle to \"{}\".",
+        puzzle_path.display()
+    );
+    Ok(())
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), io::Error> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, contents)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc::{self, Receiver},
+        thread,
+    };
+
+    use super::{AocClientError, Client};
+    use crate::day;
+    use crate::template::inputs::InvalidInput;
+
+    /// Serve one response per request on a local port. Returns the base URL and the received requests.
+    fn serve(responses: Vec<(u16, &'static str)>) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(x) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = x.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut content = vec![0; content_length];
+                reader.read_exact(&mut content).unwrap();
+                request.push_str(&String::from_utf8_lossy(&content));
+                tx.send(request).unwrap();
+
+                let response = format!(
+                    "HTTP/1.1 {status} X\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        (base_url, rx)
+    }
+
+    #[test]
+    fn downloads_input() {
+        let (base_url, requests) = serve(vec![(200, "1\n2\n")]);
+        let client = Client::new(&base_url, "abc", 2024);
+
+        assert_eq!(client.input(day!(5)).unwrap(), "1\n2\n");
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("GET /2024/day/5/input HTTP/1.1"));
+        let request = request.to_lowercase();
+        assert!(request.contains("\r\ncookie: session=abc\r\n"));
+        assert!(request.contains("\r\nuser-agent: github.com/fspoettel/advent-of-code-rust\r\n"));
+    }
+
+    #[test]
+    fn downloads_puzzle() {
+        let page = r#"<main><article class="day-desc"><h2>--- Day 5 ---</h2><p>Hi <em>there</em>.</p></article><p>Answer: <code>1</code></p></main>"#;
+        let (base_url, requests) = serve(vec![(200, page)]);
+        let client = Client::new(&format!("{base_url}/"), "abc", 2024);
+
+        assert_eq!(
+            client.puzzle(day!(5)).unwrap(),
+            "## --- Day 5 ---\n\nHi *there*.\n"
+        );
+        assert!(requests
+            .recv()
+            .unwrap()
+            .starts_with("GET /2024/day/5 HTTP/1.1"));
+    }
+
+    #[test]
+    fn submits_answers() {
+        let page = "<main><article><p>That's the right answer!</p></article></main>";
+        let (base_url, requests) = serve(vec![(200, page)]);
+        let client = Client::new(&base_url, "abc", 2024);
+
+        assert_eq!(
+            client.submit(day!(5), 2, "42").unwrap(),
+            "That's the right answer!"
+        );
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /2024/day/5/answer HTTP/1.1"));
+        assert!(request.ends_with("level=2&answer=42"));
+    }
+
+    #[test]
+    fn reports_error_status() {
+        let (base_url, _requests) = serve(vec![(400, "Puzzle inputs differ by user.")]);
+        let client = Client::new(&base_url, "abc", 2024);
+
+        assert!(matches!(
+            client.input(day!(5)),
+            Err(AocClientError::Status(400))
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_inputs() {
+        let (base_url, _requests) = serve(vec![
+            (200, "<!DOCTYPE html><html><body>Oops</body></html>"),
+            (
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+            ),
+            (
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!",
+            ),
+        ]);
+        let client = Client::new(&base_url, "abc", 2024);
+
+        for expected in [
+            InvalidInput::Html,
+            InvalidInput::LoginPage,
+            InvalidInput::Locked,
+        ] {
+            assert!(matches!(
+                client.input(day!(5)),
+                Err(AocClientError::InvalidInput(e)) if e == expected
+            ));
+        }
+    }
+}
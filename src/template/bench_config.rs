@@ -0,0 +1,173 @@
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::time::Duration;
+
+/// The budget a solution part is benched with.
+///
+/// The runner aims to spend `time` benching a part, but never takes fewer than
+/// `min_samples` or more than `max_samples` samples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BenchConfig {
+    pub time: Duration,
+    pub min_samples: u128,
+    pub max_samples: u128,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            time: Duration::from_secs(1),
+            min_samples: 10,
+            max_samples: 10000,
+        }
+    }
+}
+
+impl BenchConfig {
+    /// Creates a config from optional overrides, falling back to the defaults.
+    /// Returns [`None`] if no override was given.
+    pub fn from_options(
+        time: Option<Duration>,
+        min_samples: Option<u128>,
+        max_samples: Option<u128>,
+    ) -> Option<Self> {
+        if time.is_none() && min_samples.is_none() && max_samples.is_none() {
+            return None;
+        }
+
+        let default = Self::default();
+        Some(Self {
+            time: time.unwrap_or(default.time),
+            min_samples: min_samples.unwrap_or(default.min_samples),
+            max_samples: max_samples.unwrap_or(default.max_samples),
+        })
+    }
+
+    /// Reads the config from the arguments a solution binary was invoked with.
+    /// Arguments that are missing or invalid fall back to the defaults.
+    pub fn from_args() -> Self {
+        let args: Vec<String> = env::args().collect();
+        let value_of = |flag: &str| {
+            args.iter()
+                .position(|x| x == flag)
+                .and_then(|i| args.get(i + 1))
+        };
+
+        let default = Self::default();
+        Self {
+            time: value_of("--bench-time")
+                .and_then(|x| parse_duration(x).ok())
+                .unwrap_or(default.time),
+            min_samples: value_of("--min-samples")
+                .and_then(|x| x.parse().ok())
+                .unwrap_or(default.min_samples),
+            max_samples: value_of("--max-samples")
+                .and_then(|x| x.parse().ok())
+                .unwrap_or(default.max_samples),
+        }
+    }
+
+    /// Serializes the config to arguments that can be forwarded to a solution binary.
+    pub fn to_args(&self) -> Vec<String> {
+        vec![
+            "--bench-time".into(),
+            format!("{}ns", self.time.as_nanos()),
+            "--min-samples".into(),
+            self.min_samples.to_string(),
+            "--max-samples".into(),
+            self.max_samples.to_string(),
+        ]
+    }
+
+    /// The number of samples to take for a part whose first execution took `base_time`.
+    pub fn iterations(&self, base_time: &Duration) -> u128 {
+        let max = self.max_samples.max(self.min_samples);
+        (self.time.as_nanos() / base_time.as_nanos().max(10)).clamp(self.min_samples, max)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Parses a duration such as `500ms`, `2s` or `1.5m`. A bare number is read as seconds.
+pub fn parse_duration(s: &str) -> Result<Duration, DurationFromStrError> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+
+    let value: f64 = value.parse().map_err(|_| DurationFromStrError)?;
+    let secs = match unit.trim() {
+        "ns" => value / 1e9,
+        "us" | "µs" => value / 1e6,
+        "ms" => value / 1e3,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        _ => return Err(DurationFromStrError),
+    };
+
+    Duration::try_from_secs_f64(secs).map_err(|_| DurationFromStrError)
+}
+
+/// An error which can be returned when parsing a duration.
+#[derive(Debug)]
+pub struct DurationFromStrError;
+
+impl Error for DurationFromStrError {}
+
+impl Display for DurationFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a duration such as `500ms`, `2s` or `1m`")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{parse_duration, BenchConfig};
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("250ns").unwrap(), Duration::from_nanos(250));
+        assert_eq!(parse_duration("40us").unwrap(), Duration::from_micros(40));
+        assert_eq!(parse_duration("40µs").unwrap(), Duration::from_micros(40));
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("2s").unwrap(), Duration::from_secs(2));
+        assert_eq!(parse_duration("3").unwrap(), Duration::from_secs(3));
+        assert_eq!(parse_duration("1.5m").unwrap(), Duration::from_secs(90));
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("ms").is_err());
+        assert!(parse_duration("10h").is_err());
+        assert!(parse_duration("-1s").is_err());
+    }
+
+    #[test]
+    fn falls_back_to_defaults() {
+        assert_eq!(BenchConfig::from_options(None, None, None), None);
+
+        let config = BenchConfig::from_options(None, Some(5), None).unwrap();
+        assert_eq!(config.time, Duration::from_secs(1));
+        assert_eq!(config.min_samples, 5);
+        assert_eq!(config.max_samples, 10000);
+    }
+
+    #[test]
+    fn clamps_iterations() {
+        let config = BenchConfig {
+            time: Duration::from_millis(100),
+            min_samples: 5,
+            max_samples: 50,
+        };
+        assert_eq!(config.iterations(&Duration::from_millis(10)), 10);
+        assert_eq!(config.iterations(&Duration::from_secs(1)), 5);
+        assert_eq!(config.iterations(&Duration::from_nanos(1)), 50);
+    }
+}
@@ -0,0 +1,188 @@
+/// Renders the stored timings as an SVG bar chart, so they can be read at a glance in the readme.
+use std::{fmt::Write, fs, io::Error, time::Duration};
+
+use crate::template::paths::paths;
+use crate::template::timings::Timings;
+
+const WIDTH: f64 = 640.0;
+const MARGIN_LEFT: f64 = 60.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 30.0;
+const MARGIN_BOTTOM: f64 = 30.0;
+const ROW_HEIGHT: f64 = 24.0;
+const BAR_HEIGHT: f64 = 9.0;
+const COLORS: [&str; 2] = ["#4e79a7", "#f28e2b"];
+
+/// Write the chart to the configured chart path. Returns `false` if there was nothing to chart.
+pub fn store_file(timings: &Timings) -> Result<bool, Error> {
+    let Some(svg) = render(timings) else {
+        return Ok(false);
+    };
+
+    let path = &paths().chart;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, svg)?;
+    Ok(true)
+}
+
+/// Render the part 1 and part 2 timings of every day as horizontal bars on a log scale.
+/// Returns [`None`] if no part has a timing.
+pub fn render(timings: &Timings) -> Option<String> {
+    let nanos = timings
+        .data
+        .iter()
+        .flat_map(|t| [t.part_1, t.part_2])
+        .flatten()
+        .map(|x| x.nanos.max(1) as f64);
+
+    let min = nanos.clone().fold(f64::INFINITY, f64::min);
+    let max = nanos.fold(0_f64, f64::max);
+    if max == 0_f64 {
+        return None;
+    }
+
+    // the axis spans whole decades, with at least one between both ends.
+    let lo = min.log10().floor();
+    let hi = max.log10().ceil().max(lo + 1.0);
+
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = ROW_HEIGHT * timings.data.len() as f64;
+    let height = MARGIN_TOP + plot_height + MARGIN_BOTTOM;
+    let x = |nanos: f64| MARGIN_LEFT + (nanos.log10() - lo) / (hi - lo) * plot_width;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" viewBox="0 0 {WIDTH} {height}" font-family="sans-serif" font-size="11">"#
+    );
+    let _ = writeln!(
+        svg,
+        r##"<rect width="100%" height="100%" fill="#ffffff"/>"##
+    );
+
+    for (i, label) in ["Part 1", "Part 2"].iter().enumerate() {
+        let lx = MARGIN_LEFT + i as f64 * 70.0;
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{lx}" y="10" width="10" height="10" fill="{}"/><text x="{}" y="19">{label}</text>"#,
+            COLORS[i],
+            lx + 14.0
+        );
+    }
+
+    for decade in (lo as i32)..=(hi as i32) {
+        let tx = x(10_f64.powi(decade));
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{tx:.1}" y1="{MARGIN_TOP}" x2="{tx:.1}" y2="{:.1}" stroke="#dddddd"/><text x="{tx:.1}" y="{:.1}" text-anchor="middle">{}</text>"##,
+            MARGIN_TOP + plot_height,
+            MARGIN_TOP + plot_height + 16.0,
+            format_decade(decade)
+        );
+    }
+
+    for (row, timing) in timings.data.iter().enumerate() {
+        let y = MARGIN_TOP + row as f64 * ROW_HEIGHT;
+        let day = timing.day.into_inner();
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="end">Day {day}</text>"#,
+            MARGIN_LEFT - 8.0,
+            y + ROW_HEIGHT / 2.0 + 4.0
+        );
+
+        for (i, part) in [timing.part_1, timing.part_2].iter().enumerate() {
+            let Some(part) = part else {
+                continue;
+            };
+            let nanos = part.nanos.max(1) as f64;
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{MARGIN_LEFT}" y="{:.1}" width="{:.1}" height="{BAR_HEIGHT}" fill="{}"><title>Day {day} part {}: {part}</title></rect>"#,
+                y + 3.0 + i as f64 * BAR_HEIGHT,
+                (x(nanos) - MARGIN_LEFT).max(1.0),
+                COLORS[i],
+                i + 1
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    Some(svg)
+}
+
+/// Formats `10^decade` nanoseconds as a tick label, e.g. `100µs`.
+fn format_decade(decade: i32) -> String {
+    format!(
+        "{:?}",
+        Duration::from_nanos(10_u64.pow(decade.max(0) as u32))
+    )
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{format_decade, render};
+    use crate::{
+        day,
+        template::timings::{Timing, Timings},
+    };
+
+    fn get_mock_timings() -> Timings {
+        Timings {
+            data: vec![
+                Timing {
+                    day: day!(1),
+                    parse: None,
+                    part_1: Some(Duration::from_micros(50).into()),
+                    part_2: Some(Duration::from_millis(2).into()),
+                    total_nanos: 2_050_000_f64,
+                    failures: vec![],
+                    memory: None,
+                },
+                Timing {
+                    day: day!(2),
+                    parse: None,
+                    part_1: Some(Duration::from_millis(30).into()),
+                    part_2: None,
+                    total_nanos: 30_000_000_f64,
+                    failures: vec![],
+                    memory: None,
+                },
+            ],
+            environment: None,
+        }
+    }
+
+    #[test]
+    fn formats_decades() {
+        assert_eq!(format_decade(0), "1ns");
+        assert_eq!(format_decade(4), "10µs");
+        assert_eq!(format_decade(9), "1s");
+    }
+
+    #[test]
+    fn renders_chart() {
+        let svg = render(&get_mock_timings()).unwrap();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(">Day 1</text>"));
+        assert!(svg.contains(">Day 2</text>"));
+        assert_eq!(svg.matches("<title>").count(), 3);
+        assert!(svg.contains("<title>Day 1 part 2: 2.0ms</title>"));
+        // the axis spans 10µs to 100ms.
+        assert!(svg.contains(">10µs</text>"));
+        assert!(svg.contains(">100ms</text>"));
+        assert!(!svg.contains(">1µs</text>"));
+    }
+
+    #[test]
+    fn skips_empty_timings() {
+        assert!(render(&Timings::default()).is_none());
+    }
+}
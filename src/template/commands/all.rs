@@ -1,5 +1,15 @@
-use crate::template::{all_days, run_multi::run_multi};
+use std::process;
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+use crate::template::all_days;
+use crate::template::readme_stars;
+use crate::template::run_multi::{run_multi, RunOptions};
+
+pub fn handle(options: &RunOptions) {
+    let stars = readme_stars::read().unwrap_or_default();
+    let summary = run_multi(&all_days().collect(), options);
+    readme_stars::update_readme(&stars);
+
+    if summary.incorrect > 0 || summary.failed > 0 {
+        process::exit(1);
+    }
 }
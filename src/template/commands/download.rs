@@ -1,14 +1,13 @@
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_client, examples, Day};
 use std::process;
 
-pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
-    }
-
-    if let Err(e) = aoc_cli::download(day) {
-        eprintln!("failed to call aoc-cli: {e}");
+pub fn handle(day: Day, force: bool) {
+    if let Err(e) = aoc_client::download(day, force) {
+        eprintln!("failed to download day {day}: {e}");
         process::exit(1);
     };
+
+    if let Err(e) = examples::write(day) {
+        eprintln!("failed to extract the examples of day {day}: {e}");
+    }
 }
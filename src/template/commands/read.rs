@@ -1,15 +1,14 @@
 use std::process;
 
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_client, examples, Day};
 
 pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
-    }
-
-    if let Err(e) = aoc_cli::read(day) {
-        eprintln!("failed to call aoc-cli: {e}");
+    if let Err(e) = aoc_client::read(day) {
+        eprintln!("failed to read day {day}: {e}");
         process::exit(1);
     };
+
+    if let Err(e) = examples::write(day) {
+        eprintln!("failed to extract the examples of day {day}: {e}");
+    }
 }
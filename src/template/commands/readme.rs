@@ -0,0 +1,15 @@
+use std::process;
+
+use crate::template::readme;
+
+/// Regenerate all managed sections of the readme from the stored data.
+pub fn handle() {
+    match readme::update(None) {
+        Ok(updated) if updated.is_empty() => println!("No readme sections to update."),
+        Ok(updated) => println!("Updated readme sections: {}.", updated.join(", ")),
+        Err(e) => {
+            eprintln!("Failed to update the readme: {e}");
+            process::exit(1);
+        }
+    }
+}
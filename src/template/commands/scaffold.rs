@@ -1,9 +1,10 @@
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io::Write,
     process,
 };
 
+use crate::template::paths::paths;
 use crate::template::Day;
 
 const MODULE_TEMPLATE: &str =
@@ -28,9 +29,20 @@ fn create_file(path: &str) -> Result<File, std::io::Error> {
 }
 
 pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+    let paths = paths();
+    let input_path = paths.input(day).display().to_string();
+    let example_path = paths.example(day).display().to_string();
+    let module_path = paths.bin_path(day);
+
+    // the data directories of a year do not exist before its first day is scaffolded.
+    for path in [paths.input(day), paths.example(day)] {
+        if let Some(dir) = path.parent() {
+            if let Err(e) = fs::create_dir_all(dir) {
+                eprintln!("Failed to create data directory: {e}");
+                process::exit(1);
+            }
+        }
+    }
 
     let mut file = match safe_create_file(&module_path, overwrite) {
         Ok(file) => file,
@@ -75,5 +87,8 @@ pub fn handle(day: Day, overwrite: bool) {
     }
 
     println!("---");
-    println!("🎄 Type `cargo solve {day}` to run your solution.");
+    match paths.year {
+        Some(year) => println!("🎄 Type `cargo solve {day} --year {year}` to run your solution."),
+        None => println!("🎄 Type `cargo solve {day}` to run your solution."),
+    }
 }
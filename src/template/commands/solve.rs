@@ -1,9 +1,29 @@
-use std::process::{Command, Stdio};
+use std::process::{self, Command, Stdio};
+use std::time::Duration;
 
-use crate::template::Day;
+use crate::template::paths::paths;
+use crate::template::readme_stars;
+use crate::template::{BenchConfig, Day};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    memory: bool,
+    submit_part: Option<u8>,
+    force: bool,
+    bench: Option<BenchConfig>,
+    record: bool,
+    timeout: Option<Duration>,
+) {
+    // both install a global allocator, see the `compile_error!` in `template/mod.rs`.
+    if dhat && memory {
+        eprintln!("`--dhat` and `--memory` can not be combined, pass only one of them.");
+        process::exit(1);
+    }
+
+    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), paths().bin(day)];
 
     if dhat {
         cmd_args.extend([
@@ -12,17 +32,41 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
             "--features".to_string(),
             "dhat-heap".to_string(),
         ]);
-    } else if release {
-        cmd_args.push("--release".to_string());
+    } else {
+        if release {
+            cmd_args.push("--release".to_string());
+        }
+        if memory {
+            cmd_args.extend(["--features".to_string(), "count-alloc".to_string()]);
+        }
     }
 
     cmd_args.push("--".to_string());
 
+    if let Some(bench) = bench {
+        cmd_args.push("--time".to_string());
+        cmd_args.extend(bench.to_args());
+    }
+
+    if record {
+        cmd_args.push("--record".to_string());
+    }
+
+    if let Some(timeout) = timeout {
+        cmd_args.push("--timeout".to_string());
+        cmd_args.push(format!("{}ns", timeout.as_nanos()));
+    }
+
     if let Some(submit_part) = submit_part {
         cmd_args.push("--submit".to_string());
         cmd_args.push(submit_part.to_string());
+        if force {
+            cmd_args.push("--force".to_string());
+        }
     }
 
+    let stars = readme_stars::read().unwrap_or_default();
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
@@ -31,4 +75,5 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         .unwrap();
 
     cmd.wait().unwrap();
+    readme_stars::update_readme(&stars);
 }
@@ -1,15 +1,56 @@
 use std::collections::HashSet;
+use std::process;
 
-use crate::template::run_multi::run_multi;
-use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::compare;
+use crate::template::export::Export;
+use crate::template::history;
+use crate::template::readme;
+use crate::template::readme_stars;
+use crate::template::run_multi::{run_multi, RunOptions};
+use crate::template::timings::{MergeStrategy, Timings};
+use crate::template::{all_days, Day, ANSI_BOLD, ANSI_RESET};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
-    let stored_timings = Timings::read_from_file();
+/// Bench the solutions. With `compare`, the results are compared against the stored timings,
+/// and with `fail_above`, the process exits non-zero if a phase got slower by more than that percentage.
+/// With `export`, the timings are exported. Without a day or one of the other flags, the stored
+/// timings are exported without benching. With `store`, new timings are merged into the stored
+/// ones using `merge`.
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    merge: MergeStrategy,
+    compare: bool,
+    fail_above: Option<f64>,
+    export: Option<Export>,
+    options: &RunOptions,
+) {
+    let compare = compare || fail_above.is_some();
+
+    // the stored timings are only needed to skip complete days, unless they are stored, compared or exported.
+    let stored_timings = match Timings::read_from_file() {
+        Ok(timings) => timings,
+        Err(e) if store || compare || export.is_some() => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            Timings::default()
+        }
+    };
+
+    if let Some(export) = &export {
+        if day.is_none() && !run_all && !store && !compare {
+            write_export(export, &stored_timings);
+            return;
+        }
+    }
 
     let days_to_run = day.map_or_else(
         || {
-            if run_all {
+            if run_all || compare {
                 all_days().collect()
             } else {
                 // when the `--all` flag is not set, filter out days that are fully benched.
@@ -21,20 +62,80 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let stars = readme_stars::read().unwrap_or_default();
+    let summary = run_multi(&days_to_run, options);
+    let timings = summary.timings.unwrap();
+    readme_stars::update_readme(&stars);
+
+    // failed parts and wrong answers are reported by `run_multi`.
+    let mut regressed = fail_above.is_some() && (summary.failed > 0 || summary.incorrect > 0);
+
+    if compare {
+        let deltas = compare::compare(&stored_timings, &timings);
+
+        println!("\n{ANSI_BOLD}Compared to stored timings{ANSI_RESET}\n");
+        compare::print_table(&deltas);
+
+        if let Some(pct) = fail_above {
+            let regressions = compare::regressions(&deltas, pct);
+            if !regressions.is_empty() {
+                println!(
+                    "\n{ANSI_BOLD}✘ {} phase(s) got slower by more than {pct}% or are missing.{ANSI_RESET}",
+                    regressions.len()
+                );
+                regressed = true;
+            }
+        }
+    }
 
     if store {
-        let merged_timings = stored_timings.merge(&timings);
+        let history: Vec<Timings> = match merge {
+            MergeStrategy::Median(_) => history::read_from_file()
+                .into_iter()
+                .map(|entry| entry.timings)
+                .collect(),
+            _ => vec![],
+        };
+
+        if let Err(e) = history::append(&timings) {
+            eprintln!("Failed to append timings to history: {e}");
+        }
+
+        let merged_timings = stored_timings.merge_with(&timings, merge, &history);
         merged_timings.store_file().unwrap();
 
         println!();
-        match readme_benchmarks::update(merged_timings) {
-            Ok(()) => {
+        match readme::update(Some(&["benchmarks", "status"])) {
+            Ok(_) => {
                 println!("Stored updated benchmarks.");
             }
-            Err(_) => {
-                eprintln!("Failed to store updated benchmarks.");
+            Err(e) => {
+                eprintln!("Failed to store updated benchmarks: {e}");
             }
         }
     }
+
+    if let Some(export) = &export {
+        write_export(export, &timings);
+    }
+
+    if regressed {
+        process::exit(1);
+    }
+}
+
+fn write_export(export: &Export, timings: &Timings) {
+    if let Err(e) = export.write(timings) {
+        eprintln!("Failed to export timings: {e}");
+        process::exit(1);
+    }
+
+    if let Some(out) = &export.out {
+        println!("Exported timings to {out}.");
+    }
+}
+
+/// Print how the timings of a day changed across the runs stored in the history.
+pub fn handle_history(day: Day) {
+    history::print_trend(&history::read_from_file(), day);
 }
@@ -0,0 +1,211 @@
+/// Compares freshly measured timings against a stored baseline.
+use std::time::Duration;
+
+use crate::template::timings::{Timing, Timings};
+use crate::template::{Day, ANSI_BOLD, ANSI_GREEN, ANSI_RED, ANSI_RESET};
+
+/// Changes within this range (in percent) are considered noise and are not highlighted.
+const NOISE_PCT: f64 = 5.0;
+
+/// The change of a single phase of a day between two sets of timings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Delta {
+    pub day: Day,
+    pub phase: &'static str,
+    pub old: Option<Duration>,
+    pub new: Option<Duration>,
+}
+
+impl Delta {
+    /// Relative change from the old to the new timing in percent, if both are present.
+    pub fn change_pct(&self) -> Option<f64> {
+        let old = self.old?.as_nanos() as f64;
+        let new = self.new?.as_nanos() as f64;
+
+        if old == 0.0 {
+            return None;
+        }
+
+        Some((new - old) / old * 100.0)
+    }
+
+    /// Whether the phase was stored, but has no timing now, e.g. because it panicked or timed out.
+    pub fn is_missing(&self) -> bool {
+        self.old.is_some() && self.new.is_none()
+    }
+}
+
+fn phases(timing: &Timing) -> [(&'static str, Option<Duration>); 3] {
+    [
+        ("Parse", timing.parse.map(|x| x.duration())),
+        ("Part 1", timing.part_1.map(|x| x.duration())),
+        ("Part 2", timing.part_2.map(|x| x.duration())),
+    ]
+}
+
+/// Pair the phases of the newly measured days with the stored baseline.
+/// Phases that were neither stored nor measured are skipped.
+pub fn compare(baseline: &Timings, current: &Timings) -> Vec<Delta> {
+    let mut deltas = vec![];
+
+    for timing in &current.data {
+        let stored = baseline.data.iter().find(|t| t.day == timing.day);
+
+        for (i, (phase, new)) in phases(timing).into_iter().enumerate() {
+            let old = stored.and_then(|t| phases(t)[i].1);
+
+            if old.is_none() && new.is_none() {
+                continue;
+            }
+
+            deltas.push(Delta {
+                day: timing.day,
+                phase,
+                old,
+                new,
+            });
+        }
+    }
+
+    deltas
+}
+
+/// The deltas that got slower by more than `pct` percent, or are missing now.
+pub fn regressions(deltas: &[Delta], pct: f64) -> Vec<&Delta> {
+    deltas
+        .iter()
+        .filter(|d| d.is_missing() || d.change_pct().is_some_and(|x| x > pct))
+        .collect()
+}
+
+pub fn print_table(deltas: &[Delta]) {
+    let format = |x: Option<Duration>| x.map_or_else(|| "-".into(), |x| format!("{x:.1?}"));
+
+    println!(
+        "{ANSI_BOLD}{:<6} {:<8} {:>10} {:>10} {:>9}{ANSI_RESET}",
+        "Day", "Phase", "Old", "New", "Change"
+    );
+
+    for delta in deltas {
+        let change = match delta.change_pct() {
+            Some(pct) => {
+                let color = if pct < -NOISE_PCT {
+                    ANSI_GREEN
+                } else if pct > NOISE_PCT {
+                    ANSI_RED
+                } else {
+                    ""
+                };
+                format!("{color}{:>+8.1}%{ANSI_RESET}", pct)
+            }
+            None if delta.is_missing() => format!("{ANSI_RED}{:>9}{ANSI_RESET}", "missing"),
+            None => format!("{:>9}", "-"),
+        };
+
+        println!(
+            "{:<6} {:<8} {:>10} {:>10} {change}",
+            delta.day.to_string(),
+            delta.phase,
+            format(delta.old),
+            format(delta.new),
+        );
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{compare, regressions, Delta};
+    use crate::{
+        day,
+        template::timings::{Timing, Timings},
+        template::Day,
+    };
+
+    fn timing(day: u8, part_1: Option<Duration>, part_2: Option<Duration>) -> Timing {
+        Timing {
+            day: Day::new(day).unwrap(),
+            parse: None,
+            part_1: part_1.map(Into::into),
+            part_2: part_2.map(Into::into),
+            total_nanos: 0.0,
+            failures: vec![],
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn computes_change() {
+        let delta = Delta {
+            day: day!(1),
+            phase: "Part 1",
+            old: Some(Duration::from_millis(10)),
+            new: Some(Duration::from_millis(15)),
+        };
+        assert_eq!(delta.change_pct(), Some(50.0));
+
+        let delta = Delta { old: None, ..delta };
+        assert_eq!(delta.change_pct(), None);
+    }
+
+    #[test]
+    fn compares_timings() {
+        let baseline = Timings {
+            data: vec![timing(
+                1,
+                Some(Duration::from_millis(10)),
+                Some(Duration::from_secs(2)),
+            )],
+            environment: None,
+        };
+        let current = Timings {
+            data: vec![
+                timing(
+                    1,
+                    Some(Duration::from_millis(5)),
+                    Some(Duration::from_secs(3)),
+                ),
+                timing(2, Some(Duration::from_micros(80)), None),
+            ],
+            environment: None,
+        };
+
+        let deltas = compare(&baseline, &current);
+        assert_eq!(deltas.len(), 3);
+        assert_eq!(deltas[0].change_pct(), Some(-50.0));
+        assert_eq!(deltas[1].change_pct(), Some(50.0));
+        assert_eq!(deltas[2].old, None);
+        assert_eq!(deltas[2].new, Some(Duration::from_micros(80)));
+
+        let slower = regressions(&deltas, 10.0);
+        assert_eq!(slower.len(), 1);
+        assert_eq!(slower[0].phase, "Part 2");
+        assert_eq!(regressions(&deltas, 60.0).len(), 0);
+    }
+
+    #[test]
+    fn treats_missing_timings_as_regressions() {
+        let baseline = Timings {
+            data: vec![timing(
+                1,
+                Some(Duration::from_millis(10)),
+                Some(Duration::from_secs(2)),
+            )],
+            environment: None,
+        };
+        let current = Timings {
+            data: vec![timing(1, Some(Duration::from_millis(10)), None)],
+            environment: None,
+        };
+
+        let deltas = compare(&baseline, &current);
+        assert!(deltas[1].is_missing());
+
+        let slower = regressions(&deltas, 10.0);
+        assert_eq!(slower.len(), 1);
+        assert_eq!(slower[0].phase, "Part 2");
+    }
+}
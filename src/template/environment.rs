@@ -0,0 +1,193 @@
+/// Describes the machine and build that produced a set of timings.
+use std::{collections::HashMap, env, fmt::Display, fs, process::Command, thread};
+use tinyjson::JsonValue;
+
+/// Benchmark environment. Values that could not be detected are [`None`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Environment {
+    pub cpu: Option<String>,
+    pub cores: Option<usize>,
+    pub rustc: Option<String>,
+    pub target: Option<String>,
+    pub profile: String,
+    /// Value of `RAYON_NUM_THREADS`, if set.
+    pub rayon_threads: Option<String>,
+    pub git_commit: Option<String>,
+}
+
+impl Environment {
+    /// Detect the environment the solutions run in.
+    /// The compiler and target are the ones the template itself was built with.
+    pub fn detect(is_release: bool) -> Self {
+        Self {
+            cpu: cpu_model(),
+            cores: thread::available_parallelism().ok().map(usize::from),
+            rustc: option_env!("AOC_RUSTC_VERSION").map(Into::into),
+            target: option_env!("AOC_TARGET").map(Into::into),
+            profile: if is_release { "release" } else { "debug" }.into(),
+            rayon_threads: env::var("RAYON_NUM_THREADS").ok(),
+            git_commit: git_commit(),
+        }
+    }
+
+    /// Whether timings measured in both environments are comparable.
+    /// The git commit is ignored, as it is expected to change between runs.
+    pub fn is_comparable(&self, other: &Self) -> bool {
+        Self {
+            git_commit: None,
+            ..self.clone()
+        } == Self {
+            git_commit: None,
+            ..other.clone()
+        }
+    }
+}
+
+impl Display for Environment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unknown = || "unknown".to_string();
+
+        write!(f, "{}", self.cpu.clone().unwrap_or_else(unknown))?;
+        if let Some(cores) = self.cores {
+            write!(f, " ({cores} cores)")?;
+        }
+        write!(
+            f,
+            ", {}, {}, {} profile",
+            self.rustc.clone().unwrap_or_else(unknown),
+            self.target.clone().unwrap_or_else(unknown),
+            self.profile
+        )?;
+        if let Some(threads) = &self.rayon_threads {
+            write!(f, ", RAYON_NUM_THREADS={threads}")?;
+        }
+        if let Some(commit) = &self.git_commit {
+            write!(f, ", commit {commit}")?;
+        }
+        Ok(())
+    }
+}
+
+fn cpu_model() -> Option<String> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+    cpuinfo
+        .lines()
+        .find(|line| line.starts_with("model name"))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, model)| model.trim().to_string())
+}
+
+fn git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Environment> for JsonValue {
+    fn from(value: &Environment) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        let string = |x: &Option<String>| x.clone().map_or(JsonValue::Null, JsonValue::String);
+
+        map.insert("cpu".into(), string(&value.cpu));
+        map.insert(
+            "cores".into(),
+            value
+                .cores
+                .map_or(JsonValue::Null, |x| JsonValue::Number(x as f64)),
+        );
+        map.insert("rustc".into(), string(&value.rustc));
+        map.insert("target".into(), string(&value.target));
+        map.insert("profile".into(), JsonValue::String(value.profile.clone()));
+        map.insert("rayon_threads".into(), string(&value.rayon_threads));
+        map.insert("git_commit".into(), string(&value.git_commit));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Environment {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected environment to be a JSON object.")?;
+
+        let string = |key: &str| json.get(key).and_then(|v| v.get::<String>()).cloned();
+
+        Ok(Environment {
+            cpu: string("cpu"),
+            cores: json
+                .get("cores")
+                .and_then(|v| v.get::<f64>())
+                .map(|x| *x as usize),
+            rustc: string("rustc"),
+            target: string("target"),
+            profile: string("profile").ok_or("Expected environment.profile to be a string.")?,
+            rayon_threads: string("rayon_threads"),
+            git_commit: string("git_commit"),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use tinyjson::JsonValue;
+
+    use super::Environment;
+
+    fn get_mock_environment() -> Environment {
+        Environment {
+            cpu: Some("AMD Ryzen 7 5800X 8-Core Processor".into()),
+            cores: Some(16),
+            rustc: Some("rustc 1.83.0 (90b35a623 2024-11-26)".into()),
+            target: Some("x86_64-unknown-linux-gnu".into()),
+            profile: "release".into(),
+            rayon_threads: None,
+            git_commit: Some("abc1234".into()),
+        }
+    }
+
+    #[test]
+    fn roundtrips_environment() {
+        let environment = get_mock_environment();
+        let json = JsonValue::from(&environment);
+        assert_eq!(Environment::try_from(&json).unwrap(), environment);
+    }
+
+    #[test]
+    fn compares_environments() {
+        let environment = get_mock_environment();
+
+        let other_commit = Environment {
+            git_commit: Some("def5678".into()),
+            ..environment.clone()
+        };
+        assert!(environment.is_comparable(&other_commit));
+
+        let other_threads = Environment {
+            rayon_threads: Some("1".into()),
+            ..environment.clone()
+        };
+        assert!(!environment.is_comparable(&other_threads));
+    }
+
+    #[test]
+    fn formats_environment() {
+        assert_eq!(
+            get_mock_environment().to_string(),
+            "AMD Ryzen 7 5800X 8-Core Processor (16 cores), rustc 1.83.0 (90b35a623 2024-11-26), x86_64-unknown-linux-gnu, release profile, commit abc1234"
+        );
+    }
+}
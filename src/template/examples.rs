@@ -0,0 +1,307 @@
+/// Extraction of the examples and their expected answers from a puzzle description in `data/puzzles/DD.md`.
+/// Understands the Markdown written by `download` and `read`, as well as raw html.
+use std::{fs, io::Error, ops::Range, path::Path};
+
+use crate::template::html;
+use crate::template::paths::paths;
+use crate::template::Day;
+
+static PART_TWO_HEADING: &str = "--- Part Two ---";
+static PUZZLE_ANSWER: &str = "your puzzle answer";
+
+/// The example of a part and the answer the puzzle expects for it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Example {
+    pub input: Option<String>,
+    pub answer: Option<String>,
+}
+
+/// The contents between each pair of delimiters, with the range they span including the delimiters.
+fn delimited<'a>(s: &'a str, open: &str, close: &str) -> Vec<(Range<usize>, &'a str)> {
+    let mut found = vec![];
+    let mut offset = 0;
+
+    while let Some(start) = s[offset..].find(open).map(|x| offset + x) {
+        let content_start = start + open.len();
+        let Some(end) = s[content_start..].find(close).map(|x| content_start + x) else {
+            break;
+        };
+        offset = end + close.len();
+        found.push((start..offset, &s[content_start..end]));
+    }
+
+    found
+}
+
+/// The code blocks of a description, either fenced or `<pre><code>`.
+fn code_blocks(s: &str) -> Vec<(Range<usize>, String)> {
+    let fenced = delimited(s, "```", "```")
+        .into_iter()
+        .map(|(i, block)| (i, block.split_once('\n').map_or("", |x| x.1).to_string()));
+    let html = delimited(s, "<pre><code>", "</code></pre>")
+        .into_iter()
+        .map(|(i, block)| (i, html::strip_tags(block)));
+
+    let mut blocks: Vec<(Range<usize>, String)> = fenced.chain(html).collect();
+    blocks.sort_by_key(|(range, _)| range.start);
+    blocks
+}
+
+/// The highlighted code of a description, e.g. ``*`11`*`` or `<code><em>11</em></code>`.
+fn highlights(s: &str) -> Vec<(usize, String)> {
+    let mut found: Vec<(usize, String)> = [
+        ("*`", "`*"),
+        ("<code><em>", "</em></code>"),
+        ("<em><code>", "</code></em>"),
+    ]
+    .iter()
+    .flat_map(|(open, close)| delimited(s, open, close))
+    .filter(|(_, x)| !x.contains(['`', '\n']))
+    .map(|(range, x)| (range.start, html::strip_tags(x).trim().to_string()))
+    .filter(|(_, x)| !x.is_empty())
+    .collect();
+
+    found.sort_by_key(|(i, _)| *i);
+    found
+}
+
+/// The example of one part: the first code block introduced as an example, and the last highlighted value
+/// before the answer of a solved part.
+fn extract_part(s: &str) -> Example {
+    let blocks = code_blocks(s);
+
+    let mut previous_end = 0;
+    let mut input = None;
+    for (range, block) in &blocks {
+        if s[previous_end..range.start]
+            .to_lowercase()
+            .contains("example")
+        {
+            input = Some(block.clone());
+            break;
+        }
+        previous_end = range.end;
+    }
+
+    let answer_start = s.to_lowercase().find(PUZZLE_ANSWER).unwrap_or(s.len());
+
+    Example {
+        input: input.or_else(|| blocks.into_iter().next().map(|x| x.1)),
+        answer: highlights(s)
+            .into_iter()
+            .take_while(|(i, _)| *i < answer_start)
+            .last()
+            .map(|x| x.1),
+    }
+}
+
+/// The examples of both parts. Part two often reuses the example of part one and has no input of its own.
+pub fn extract(description: &str) -> [Example; 2] {
+    let (one, two) = match description.find(PART_TWO_HEADING) {
+        Some(i) => description.split_at(i),
+        None => (description, ""),
+    };
+
+    [extract_part(one), extract_part(two)]
+}
+
+/// Replace the `None` expected by the scaffolded test of a part with a numeric answer.
+/// Returns `None` if the answer does not fit the `Option<u32>` returned by the scaffolded parts,
+/// or the test was changed since it was scaffolded.
+pub fn fill_test(source: &str, part: u8, answer: &str, use_part_file: bool) -> Option<String> {
+    answer.parse::<u32>().ok()?;
+
+    let name = match part {
+        1 => "fn test_part_one()",
+        _ => "fn test_part_two()",
+    };
+    let start = source.find(name)?;
+    let end = source[start..]
+        .find("#[test]")
+        .map_or(source.len(), |x| start + x);
+
+    let mut test = source[start..end].to_string();
+    let expected = "assert_eq!(result, None);";
+    if !test.contains(expected) {
+        return None;
+    }
+    test = test.replace(expected, &format!("assert_eq!(result, Some({answer}));"));
+    if use_part_file {
+        test = test.replace(
+            "read_file(\"examples\", DAY)",
+            &format!("read_file_part(\"examples\", DAY, {part})"),
+        );
+    }
+
+    Some(format!("{}{test}{}", &source[..start], &source[end..]))
+}
+
+/// Whether a file is missing or empty, so it can be written without losing work.
+fn is_blank(path: &Path) -> bool {
+    fs::read_to_string(path).map_or(true, |x| x.trim().is_empty())
+}
+
+/// Write the examples of a downloaded puzzle description and fill the expected answers into the tests
+/// of its solution. Examples that were edited and tests that were changed are left alone.
+pub fn write(day: Day) -> Result<(), Error> {
+    let paths = paths();
+    let examples = extract(&fs::read_to_string(paths.puzzle(day))?);
+
+    let [one, two] = &examples;
+    let part_two_differs = two.input.is_some() && two.input != one.input;
+
+    let files = [
+        (paths.example(day), one.input.as_ref()),
+        (
+            paths.day_part_file("examples", day, 2),
+            two.input.as_ref().filter(|_| part_two_differs),
+        ),
+    ];
+
+    for (path, input) in files {
+        let Some(input) = input else {
+            continue;
+        };
+        if is_blank(&path) {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&path, input)?;
+            println!("🎄 Wrote example to \"{}\".", path.display());
+        }
+    }
+
+    let module_path = paths.bin_path(day);
+    let Ok(original) = fs::read_to_string(&module_path) else {
+        return Ok(());
+    };
+    let mut source = original.clone();
+
+    for (part, example) in [(1, one), (2, two)] {
+        let Some(answer) = &example.answer else {
+            continue;
+        };
+        let use_part_file = part == 2 && part_two_differs;
+        if let Some(filled) = fill_test(&source, part, answer, use_part_file) {
+            source = filled;
+            println!(
+                "🎄 Expecting `{answer}` for the example of part {part} in \"{module_path}\"."
+            );
+        }
+    }
+
+    if source == original {
+        return Ok(());
+    }
+    fs::write(&module_path, source)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{extract, fill_test, Example};
+
+    const MODULE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
+
+    #[test]
+    fn extracts_markdown_examples() {
+        let description = [
+            "## --- Day 1: Historian Hysteria ---",
+            "",
+            "Throughout the Chief's office, *`50`* stars are missing.",
+            "",
+            "```",
+            "not an example",
+            "```",
+            "",
+            "For example:",
+            "",
+            "```",
+            "3   4",
+            "4   3",
+            "```",
+            "",
+            "In this example, the total distance is *`11`*.",
+            "",
+            "## --- Part Two ---",
+            "",
+            "Here are the same example lists again. The similarity score is *`31`*.",
+            "",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            extract(&description),
+            [
+                Example {
+                    input: Some("3   4\n4   3\n".into()),
+                    answer: Some("11".into()),
+                },
+                Example {
+                    input: None,
+                    answer: Some("31".into()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_html_examples() {
+        let description = [
+            "<article><h2>--- Day 3 ---</h2><p>For example:</p>",
+            "<pre><code>xmul(2,4)&amp;<em>mul</em>(3,7)\n</code></pre>",
+            "<p>The result is <code><em>161</em></code>.</p></article>",
+            "<article><h2 id=\"part2\">--- Part Two ---</h2><p>For example:</p>",
+            "<pre><code>don't()\n</code></pre><p>This time, the sum is <em><code>48</code></em>.</p></article>",
+        ]
+        .join("");
+
+        let [one, two] = extract(&description);
+        assert_eq!(one.input.as_deref(), Some("xmul(2,4)&mul(3,7)\n"));
+        assert_eq!(one.answer.as_deref(), Some("161"));
+        assert_eq!(two.input.as_deref(), Some("don't()\n"));
+        assert_eq!(two.answer.as_deref(), Some("48"));
+    }
+
+    #[test]
+    fn ignores_answers_of_solved_parts() {
+        let description = [
+            "## --- Day 1: Historian Hysteria ---",
+            "",
+            "For example:",
+            "",
+            "```",
+            "3   4",
+            "```",
+            "",
+            "In this example, the total distance is *`11`*.",
+            "",
+            "Your puzzle answer was *`1882714`*.",
+            "",
+            "The first half of this puzzle is complete! It provides one gold star: *`*`*",
+            "",
+        ]
+        .join("\n");
+
+        let [one, two] = extract(&description);
+        assert_eq!(one.answer.as_deref(), Some("11"));
+        assert_eq!(two, Example::default());
+    }
+
+    #[test]
+    fn fills_scaffolded_tests() {
+        let filled = fill_test(MODULE, 1, "11", false).unwrap();
+        let filled = fill_test(&filled, 2, "31", true).unwrap();
+
+        assert!(filled.contains("assert_eq!(result, Some(11));"));
+        assert!(filled.contains("assert_eq!(result, Some(31));"));
+        assert!(filled.contains("read_file_part(\"examples\", DAY, 2)"));
+        assert_eq!(filled.matches("read_file(\"examples\", DAY)").count(), 1);
+
+        assert_eq!(fill_test(&filled, 1, "12", false), None);
+        assert_eq!(fill_test(MODULE, 1, "ABC", false), None);
+        assert_eq!(fill_test(MODULE, 1, "4294967296", false), None);
+        assert_eq!(fill_test(MODULE, 1, "-1", false), None);
+    }
+}
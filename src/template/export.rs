@@ -0,0 +1,291 @@
+/// Export of timings in formats that spreadsheets and dashboards can read.
+/// Every format contains one row per day with the same columns.
+use std::{collections::HashMap, fmt::Display, fs, io::Error, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::json;
+use crate::template::timings::{PhaseTiming, Timing, Timings};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Markdown,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Format::Csv),
+            "md" | "markdown" => Ok(Format::Markdown),
+            "json" => Ok(Format::Json),
+            x => Err(format!(
+                "unknown export format `{x}`, expected `csv`, `md` or `json`."
+            )),
+        }
+    }
+}
+
+/// Where and how to export timings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Export {
+    pub format: Format,
+    /// The file to write to. Writes to stdout if not set.
+    pub out: Option<String>,
+}
+
+impl Export {
+    /// Export the timings to the configured destination.
+    pub fn write(&self, timings: &Timings) -> Result<(), Error> {
+        let output = format(timings, self.format);
+        match &self.out {
+            Some(path) => fs::write(path, output),
+            None => {
+                print!("{output}");
+                Ok(())
+            }
+        }
+    }
+}
+
+static COLUMNS: [&str; 13] = [
+    "day",
+    "parse_nanos",
+    "parse_samples",
+    "part_1_nanos",
+    "part_1_samples",
+    "part_2_nanos",
+    "part_2_samples",
+    "total_nanos",
+    "allocations",
+    "allocated_bytes",
+    "peak_bytes",
+    "failures",
+    "git_commit",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+enum Cell {
+    Number(u128),
+    Text(String),
+    Empty,
+}
+
+impl Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cell::Number(x) => write!(f, "{x}"),
+            Cell::Text(x) => write!(f, "{x}"),
+            Cell::Empty => Ok(()),
+        }
+    }
+}
+
+fn row(timing: &Timing, timings: &Timings) -> Vec<Cell> {
+    let number = |x: Option<u128>| x.map_or(Cell::Empty, Cell::Number);
+    let phase = |x: Option<PhaseTiming>| {
+        [
+            number(x.map(|x| x.nanos.into())),
+            number(x.and_then(|x| x.samples)),
+        ]
+    };
+
+    let failures: Vec<String> = timing
+        .failures
+        .iter()
+        .map(|f| format!("part {}: {}", f.part, f.reason))
+        .collect();
+
+    let mut cells = vec![Cell::Number(timing.day.into_inner().into())];
+    cells.extend(phase(timing.parse));
+    cells.extend(phase(timing.part_1));
+    cells.extend(phase(timing.part_2));
+    cells.push(Cell::Number(timing.total_nanos as u128));
+    cells.push(number(timing.memory.map(|m| m.allocations as u128)));
+    cells.push(number(timing.memory.map(|m| m.bytes as u128)));
+    cells.push(number(timing.memory.map(|m| m.peak as u128)));
+    cells.push(if failures.is_empty() {
+        Cell::Empty
+    } else {
+        Cell::Text(failures.join("; "))
+    });
+    cells.push(
+        timings
+            .environment
+            .as_ref()
+            .and_then(|e| e.git_commit.clone())
+            .map_or(Cell::Empty, Cell::Text),
+    );
+    cells
+}
+
+/// Format the timings of every day in the given format.
+pub fn format(timings: &Timings, format: Format) -> String {
+    let rows: Vec<Vec<Cell>> = timings.data.iter().map(|t| row(t, timings)).collect();
+
+    match format {
+        Format::Csv => format_csv(&rows),
+        Format::Markdown => format_markdown(&rows),
+        Format::Json => format_json(&rows),
+    }
+}
+
+fn format_csv(rows: &[Vec<Cell>]) -> String {
+    let escape = |cell: &Cell| {
+        let s = cell.to_string();
+        if s.contains([',', '"', '\n']) {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        } else {
+            s
+        }
+    };
+
+    let mut lines = vec![COLUMNS.join(",")];
+    for row in rows {
+        lines.push(row.iter().map(escape).collect::<Vec<_>>().join(","));
+    }
+    lines.join("\n") + "\n"
+}
+
+fn format_markdown(rows: &[Vec<Cell>]) -> String {
+    let mut lines = vec![
+        format!("| {} |", COLUMNS.join(" | ")),
+        format!("| {} |", vec!["---:"; COLUMNS.len()].join(" | ")),
+    ];
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| match cell {
+                Cell::Empty => "-".into(),
+                x => x.to_string().replace('|', "\\|"),
+            })
+            .collect();
+        lines.push(format!("| {} |", cells.join(" | ")));
+    }
+    lines.join("\n") + "\n"
+}
+
+fn format_json(rows: &[Vec<Cell>]) -> String {
+    let rows = rows
+        .iter()
+        .map(|row| {
+            let map: HashMap<String, JsonValue> = COLUMNS
+                .iter()
+                .zip(row)
+                .map(|(column, cell)| {
+                    let value = match cell {
+                        Cell::Number(x) => JsonValue::Number(*x as f64),
+                        Cell::Text(x) => JsonValue::String(x.clone()),
+                        Cell::Empty => JsonValue::Null,
+                    };
+                    ((*column).into(), value)
+                })
+                .collect();
+            JsonValue::Object(map)
+        })
+        .collect();
+
+    json::format_sorted(&JsonValue::Array(rows)) + "\n"
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{str::FromStr, time::Duration};
+
+    use tinyjson::JsonValue;
+
+    use super::{format, Format};
+    use crate::{
+        day,
+        template::{
+            memory::Memory,
+            timings::{Failure, PhaseTiming, Timing, Timings},
+        },
+    };
+
+    fn get_mock_timings() -> Timings {
+        Timings {
+            data: vec![
+                Timing {
+                    day: day!(1),
+                    parse: None,
+                    part_1: Some(PhaseTiming {
+                        nanos: 1500,
+                        samples: Some(100),
+                    }),
+                    part_2: Some(Duration::from_micros(3).into()),
+                    total_nanos: 4500_f64,
+                    failures: vec![],
+                    memory: Some(Memory {
+                        allocations: 4,
+                        bytes: 2048,
+                        peak: 1024,
+                    }),
+                },
+                Timing {
+                    day: day!(2),
+                    parse: None,
+                    part_1: None,
+                    part_2: None,
+                    total_nanos: 0_f64,
+                    failures: vec![Failure {
+                        part: 1,
+                        reason: "panicked: \"oops\", twice".into(),
+                    }],
+                    memory: None,
+                },
+            ],
+            environment: None,
+        }
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!(Format::from_str("csv"), Ok(Format::Csv));
+        assert_eq!(Format::from_str("md"), Ok(Format::Markdown));
+        assert_eq!(Format::from_str("json"), Ok(Format::Json));
+        assert!(Format::from_str("xml").is_err());
+    }
+
+    #[test]
+    fn exports_csv() {
+        let expected = [
+            "day,parse_nanos,parse_samples,part_1_nanos,part_1_samples,part_2_nanos,part_2_samples,total_nanos,allocations,allocated_bytes,peak_bytes,failures,git_commit",
+            "1,,,1500,100,3000,,4500,4,2048,1024,,",
+            "2,,,,,,,0,,,,\"part 1: panicked: \"\"oops\"\", twice\",",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(format(&get_mock_timings(), Format::Csv), expected);
+    }
+
+    #[test]
+    fn exports_markdown() {
+        let output = format(&get_mock_timings(), Format::Markdown);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("| day | parse_nanos |"));
+        assert_eq!(
+            lines[2],
+            "| 1 | - | - | 1500 | 100 | 3000 | - | 4500 | 4 | 2048 | 1024 | - | - |"
+        );
+    }
+
+    #[test]
+    fn exports_json() {
+        let output = format(&get_mock_timings(), Format::Json);
+        let json = JsonValue::from_str(&output).unwrap();
+        let rows: &Vec<JsonValue> = json.get().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["part_1_nanos"], JsonValue::Number(1500.0));
+        assert_eq!(rows[0]["part_2_samples"], JsonValue::Null);
+        assert_eq!(
+            rows[1]["failures"],
+            JsonValue::String("part 1: panicked: \"oops\", twice".into())
+        );
+    }
+}
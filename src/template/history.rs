@@ -0,0 +1,256 @@
+use chrono::DateTime;
+/// Append-only log of stored timings, one JSON line per `cargo time --store` run.
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{Error, Write},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tinyjson::JsonValue;
+
+use crate::template::json;
+use crate::template::paths::paths;
+use crate::template::timings::{PhaseTiming, Timing, Timings};
+use crate::template::{Day, ANSI_BOLD, ANSI_RESET};
+
+/// The timings of a single run, stamped with the time they were stored.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub timings: Timings,
+}
+
+impl Entry {
+    pub fn git_commit(&self) -> Option<&str> {
+        self.timings
+            .environment
+            .as_ref()
+            .and_then(|e| e.git_commit.as_deref())
+    }
+}
+
+/// Append the timings of a run to the history.
+pub fn append(timings: &Timings) -> Result<(), Error> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs());
+
+    let entry = Entry {
+        timestamp,
+        timings: timings.clone(),
+    };
+
+    let line = json::stringify_sorted(&JsonValue::from(&entry));
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(paths().history())?;
+    writeln!(file, "{line}")
+}
+
+/// Read all entries of the history. Lines that can not be parsed are skipped.
+pub fn read_from_file() -> Vec<Entry> {
+    fs::read_to_string(paths().history())
+        .map(|s| {
+            s.lines()
+                .filter_map(|line| Entry::from_line(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The fastest timing of each phase of a day across all entries, with the entry it was measured in.
+pub fn best(entries: &[Entry], day: Day) -> [Option<(Duration, &Entry)>; 3] {
+    let mut best: [Option<(Duration, &Entry)>; 3] = [None; 3];
+
+    for entry in entries {
+        let Some(timing) = entry.timings.data.iter().find(|t| t.day == day) else {
+            continue;
+        };
+
+        for (i, value) in phases(timing).into_iter().enumerate() {
+            let Some(duration) = value.map(|x| x.duration()) else {
+                continue;
+            };
+
+            if best[i].map_or(true, |(b, _)| duration < b) {
+                best[i] = Some((duration, entry));
+            }
+        }
+    }
+
+    best
+}
+
+fn phases(timing: &Timing) -> [Option<PhaseTiming>; 3] {
+    [timing.parse, timing.part_1, timing.part_2]
+}
+
+/// Print every stored run of a day, followed by the best-ever timing of each phase.
+pub fn print_trend(entries: &[Entry], day: Day) {
+    let runs: Vec<(&Entry, &Timing)> = entries
+        .iter()
+        .filter_map(|e| e.timings.data.iter().find(|t| t.day == day).map(|t| (e, t)))
+        .collect();
+
+    println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+    println!("------");
+
+    if runs.is_empty() {
+        println!("No stored timings.");
+        return;
+    }
+
+    println!(
+        "{ANSI_BOLD}{:<17} {:<8} {:>10} {:>10} {:>10}{ANSI_RESET}",
+        "Stored", "Commit", "Parse", "Part 1", "Part 2"
+    );
+
+    for (entry, timing) in runs {
+        let [parse, part_1, part_2] =
+            phases(timing).map(|x| x.map_or_else(|| "-".into(), |x| x.to_string()));
+        println!(
+            "{:<17} {:<8} {parse:>10} {part_1:>10} {part_2:>10}",
+            format_timestamp(entry.timestamp),
+            entry.git_commit().unwrap_or("-"),
+        );
+    }
+
+    println!();
+    for (label, best) in ["Parse", "Part 1", "Part 2"].iter().zip(best(entries, day)) {
+        if let Some((duration, entry)) = best {
+            println!(
+                "Best {label}: {ANSI_BOLD}{duration:.1?}{ANSI_RESET} ({}, commit {})",
+                format_timestamp(entry.timestamp),
+                entry.git_commit().unwrap_or("unknown")
+            );
+        }
+    }
+}
+
+/// Formats a unix timestamp as `YYYY-MM-DD HH:MM` in UTC.
+fn format_timestamp(timestamp: u64) -> String {
+    i64::try_from(timestamp)
+        .ok()
+        .and_then(|x| DateTime::from_timestamp(x, 0))
+        .map_or_else(
+            || timestamp.to_string(),
+            |x| x.format("%Y-%m-%d %H:%M").to_string(),
+        )
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl Entry {
+    fn from_line(line: &str) -> Result<Self, String> {
+        let json = JsonValue::from_str(line).or(Err("not valid JSON."))?;
+
+        let timestamp = json
+            .get::<HashMap<String, JsonValue>>()
+            .and_then(|map| map.get("timestamp"))
+            .and_then(|v| v.get::<f64>())
+            .ok_or("Expected entry.timestamp to be a number.")?;
+
+        let timings = json
+            .get::<HashMap<String, JsonValue>>()
+            .and_then(|map| map.get("timings"))
+            .ok_or("Expected entry.timings to be present.")?;
+
+        Ok(Entry {
+            timestamp: *timestamp as u64,
+            timings: Timings::try_from(timings)?,
+        })
+    }
+}
+
+impl From<&Entry> for JsonValue {
+    fn from(value: &Entry) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(value.timestamp as f64),
+        );
+        map.insert("timings".into(), JsonValue::from(value.timings.clone()));
+
+        JsonValue::Object(map)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use tinyjson::JsonValue;
+
+    use super::{best, format_timestamp, Entry};
+    use crate::{
+        day,
+        template::timings::{Timing, Timings},
+    };
+
+    fn entry(timestamp: u64, part_1: Duration, part_2: Option<Duration>) -> Entry {
+        Entry {
+            timestamp,
+            timings: Timings {
+                data: vec![Timing {
+                    day: day!(5),
+                    parse: None,
+                    part_1: Some(part_1.into()),
+                    part_2: part_2.map(Into::into),
+                    total_nanos: 0.0,
+                    failures: vec![],
+                    memory: None,
+                }],
+                environment: None,
+            },
+        }
+    }
+
+    #[test]
+    fn formats_timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1_733_011_200 + 3723), "2024-12-01 01:02");
+        assert_eq!(format_timestamp(1_709_164_800), "2024-02-29 00:00");
+    }
+
+    #[test]
+    fn roundtrips_entries() {
+        let entry = entry(1_733_011_200, Duration::from_micros(1500), None);
+        let line = JsonValue::from(&entry).stringify().unwrap();
+        let parsed = Entry::from_line(&line).unwrap();
+        assert_eq!(parsed.timestamp, entry.timestamp);
+        assert_eq!(parsed.timings.data[0].part_1.unwrap().nanos, 1_500_000);
+    }
+
+    #[test]
+    fn finds_best_timings() {
+        let entries = [
+            entry(1, Duration::from_millis(2), Some(Duration::from_secs(1))),
+            entry(2, Duration::from_millis(1), None),
+            entry(
+                3,
+                Duration::from_millis(3),
+                Some(Duration::from_millis(900)),
+            ),
+        ];
+
+        let [parse, part_1, part_2] = best(&entries, day!(5));
+        assert!(parse.is_none());
+
+        let (duration, entry) = part_1.unwrap();
+        assert_eq!(duration, Duration::from_millis(1));
+        assert_eq!(entry.timestamp, 2);
+
+        let (duration, entry) = part_2.unwrap();
+        assert_eq!(duration, Duration::from_millis(900));
+        assert_eq!(entry.timestamp, 3);
+
+        assert!(best(&entries, day!(6)).iter().all(Option::is_none));
+    }
+}
@@ -0,0 +1,318 @@
+/// Conversion of the pages served by Advent of Code to Markdown and plain text.
+/// Only covers the markup used in puzzle descriptions and answer responses.
+use std::iter::Peekable;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Open { name: &'a str, attrs: &'a str },
+    Close(&'a str),
+    Text(&'a str),
+}
+
+/// Split html into tags and text. Comments, doctypes and unterminated tags are skipped.
+fn tokens(html: &str) -> impl Iterator<Item = Token<'_>> {
+    let mut rest = html;
+
+    std::iter::from_fn(move || loop {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let Some(tag) = rest.strip_prefix('<') else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let (text, tail) = rest.split_at(end);
+            rest = tail;
+            return Some(Token::Text(text));
+        };
+
+        if let Some(comment) = tag.strip_prefix("!--") {
+            rest = comment.split_once("-->").map_or("", |(_, tail)| tail);
+            continue;
+        }
+
+        let Some((tag, tail)) = tag.split_once('>') else {
+            rest = "";
+            return None;
+        };
+        rest = tail;
+
+        let tag = tag.trim_end_matches('/').trim();
+        if let Some(name) = tag.strip_prefix('/') {
+            return Some(Token::Close(name.trim()));
+        }
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        }
+
+        let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        return Some(Token::Open { name, attrs });
+    })
+}
+
+/// The value of an attribute, e.g. `href`.
+fn attr<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let start = attrs.find(&format!("{name}="))? + name.len() + 1;
+    let value = &attrs[start..];
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    value[1..].split(quote).next()
+}
+
+/// Decode the entities that appear in puzzle pages.
+pub fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .and_then(|x| u32::from_str_radix(x, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|x| x.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// The inner html of every `<article>` element.
+pub fn articles(html: &str) -> Vec<&str> {
+    let mut articles = vec![];
+    let mut rest = html;
+
+    while let Some(start) = rest.find("<article") {
+        let Some(content_start) = rest[start..].find('>').map(|x| start + x + 1) else {
+            break;
+        };
+        let Some(end) = rest[content_start..].find("</article>") else {
+            break;
+        };
+        articles.push(&rest[content_start..content_start + end]);
+        rest = &rest[content_start + end..];
+    }
+
+    articles
+}
+
+/// Whether the next token opens the given element.
+fn next_opens<'a>(tokens: &mut Peekable<impl Iterator<Item = Token<'a>>>, tag: &str) -> bool {
+    matches!(tokens.peek(), Some(Token::Open { name, .. }) if *name == tag)
+}
+
+/// Whether the next token closes the given element.
+fn next_closes<'a>(tokens: &mut Peekable<impl Iterator<Item = Token<'a>>>, tag: &str) -> bool {
+    matches!(tokens.peek(), Some(Token::Close(name)) if *name == tag)
+}
+
+/// Convert a html fragment to Markdown.
+/// Example blocks become fenced code blocks, and highlighted code like `<code><em>42</em></code>`
+/// becomes ``*`42`*``.
+pub fn to_markdown(html: &str) -> String {
+    let mut out = String::new();
+    let mut tokens = tokens(html).peekable();
+    let mut is_pre = false;
+    let mut is_code = false;
+    let mut is_highlight = false;
+    let mut links: Vec<String> = vec![];
+
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Open { name, attrs } => match name {
+                "h2" => out.push_str("## "),
+                "pre" => {
+                    is_pre = true;
+                    out.push_str("```\n");
+                }
+                "code" if !is_pre => {
+                    is_code = true;
+                    if next_opens(&mut tokens, "em") {
+                        tokens.next();
+                        is_highlight = true;
+                        out.push('*');
+                    }
+                    out.push('`');
+                }
+                "em" if !is_pre && !is_code => out.push('*'),
+                "li" => out.push_str("- "),
+                "a" => {
+                    links.push(attr(attrs, "href").unwrap_or_default().to_string());
+                    out.push('[');
+                }
+                "br" => out.push('\n'),
+                _ => {}
+            },
+            Token::Close(name) => match name {
+                "h2" | "p" | "ul" => out.push_str("\n\n"),
+                "pre" => {
+                    is_pre = false;
+                    if !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    out.push_str("```\n\n");
+                }
+                "em" if is_highlight && next_closes(&mut tokens, "code") => {
+                    tokens.next();
+                    is_code = false;
+                    is_highlight = false;
+                    out.push_str("`*");
+                }
+                "em" if !is_pre && !is_code => out.push('*'),
+                "code" if !is_pre => {
+                    out.push('`');
+                    if is_highlight {
+                        out.push('*');
+                    }
+                    is_code = false;
+                    is_highlight = false;
+                }
+                "li" => out.push('\n'),
+                "a" => {
+                    let href = links.pop().unwrap_or_default();
+                    out.push_str(&format!("]({href})"));
+                }
+                _ => {}
+            },
+            Token::Text(text) => {
+                let text = decode_entities(text);
+                if is_pre {
+                    out.push_str(&text);
+                } else {
+                    push_collapsed(&mut out, &text);
+                }
+            }
+        }
+    }
+
+    let lines: Vec<&str> = out.lines().map(str::trim_end).collect();
+    let mut markdown = lines.join("\n");
+    while markdown.contains("\n\n\n") {
+        markdown = markdown.replace("\n\n\n", "\n\n");
+    }
+    markdown.trim().to_string() + "\n"
+}
+
+/// Convert a html fragment to plain text on a single line.
+pub fn to_text(html: &str) -> String {
+    let mut out = String::new();
+    for token in tokens(html) {
+        if let Token::Text(text) = token {
+            push_collapsed(&mut out, &decode_entities(text));
+        }
+    }
+    out.trim().to_string()
+}
+
+/// The text of a html fragment with the tags removed and whitespace kept.
+pub fn strip_tags(html: &str) -> String {
+    tokens(html)
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(decode_entities(text)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Append text with runs of whitespace collapsed to a single space.
+fn push_collapsed(out: &mut String, text: &str) {
+    for (i, word) in text.split(char::is_whitespace).enumerate() {
+        if i > 0 && !out.ends_with([' ', '\n']) && !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{articles, decode_entities, strip_tags, to_markdown, to_text};
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(
+            decode_entities("a &lt;b&gt; &amp; &#39;c&#x27; &unknown; &"),
+            "a <b> & 'c' &unknown; &"
+        );
+    }
+
+    #[test]
+    fn finds_articles() {
+        let html = r#"<main><article class="day-desc"><h2>a</h2></article><p>x</p><article class="day-desc">b</article></main>"#;
+        assert_eq!(articles(html), vec!["<h2>a</h2>", "b"]);
+    }
+
+    #[test]
+    fn converts_to_markdown() {
+        let html = [
+            "<h2>--- Day 1: Test ---</h2><p>The <em>elves</em> need\n",
+            "<a href=\"/2024/day/1/input\">your input</a>.</p>",
+            "<p>For example:</p>\n<pre><code>3   4\n4   3\n<em>2</em>   5\n</code></pre>",
+            "<ul><li>A &lt; B</li><li><code>x</code></li></ul>",
+            "<p>The answer is <code><em>11</em></code>.</p>",
+        ]
+        .join("");
+
+        let expected = [
+            "## --- Day 1: Test ---",
+            "",
+            "The *elves* need [your input](/2024/day/1/input).",
+            "",
+            "For example:",
+            "",
+            "```",
+            "3   4",
+            "4   3",
+            "2   5",
+            "```",
+            "",
+            "- A < B",
+            "- `x`",
+            "",
+            "The answer is *`11`*.",
+            "",
+        ]
+        .join("\n");
+
+        assert_eq!(to_markdown(&html), expected);
+    }
+
+    #[test]
+    fn converts_to_text() {
+        let html = "<p>That's not the right answer; your answer is too high.\n  <a href=\"/2024/day/1\">[Return to Day 1]</a></p>";
+        assert_eq!(
+            to_text(html),
+            "That's not the right answer; your answer is too high. [Return to Day 1]"
+        );
+    }
+
+    #[test]
+    fn strips_tags() {
+        assert_eq!(strip_tags("1 &lt; <em>2</em>\n  3"), "1 < 2\n  3");
+    }
+}
@@ -0,0 +1,188 @@
+/// Safety checks for downloaded puzzle inputs.
+/// Responses are validated before they replace an input, and the hash of every downloaded input is
+/// stored in `data/input_hashes.json` to notice when an input changes.
+use std::{collections::HashMap, fmt::Display, fs, io::Error, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::json;
+use crate::template::paths::paths;
+use crate::template::Day;
+
+/// Why a response is not a puzzle input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidInput {
+    Empty,
+    /// The puzzle is not unlocked yet.
+    Locked,
+    /// The session cookie is missing or expired.
+    LoginPage,
+    /// Some other web page.
+    Html,
+}
+
+impl Display for InvalidInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidInput::Empty => write!(f, "the response is empty."),
+            InvalidInput::Locked => write!(f, "the puzzle is not unlocked yet."),
+            InvalidInput::LoginPage => write!(
+                f,
+                "the server asks to log in, the session cookie is probably expired."
+            ),
+            InvalidInput::Html => write!(f, "the response is a web page, not a puzzle input."),
+        }
+    }
+}
+
+/// Check that a response looks like a puzzle input.
+pub fn validate(body: &str) -> Result<(), InvalidInput> {
+    let lowercase = body.to_lowercase();
+
+    if body.trim().is_empty() {
+        Err(InvalidInput::Empty)
+    } else if lowercase.contains("please don't repeatedly request this endpoint") {
+        Err(InvalidInput::Locked)
+    } else if lowercase.contains("please log in") || lowercase.contains("/auth/login") {
+        Err(InvalidInput::LoginPage)
+    } else if ["<!doctype", "<html", "<body"]
+        .iter()
+        .any(|x| lowercase.contains(x))
+    {
+        Err(InvalidInput::Html)
+    } else {
+        Ok(())
+    }
+}
+
+/// A stable hash of an input (64-bit FNV-1a), as hex.
+pub fn hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// The hashes of the downloaded inputs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputHashes {
+    pub data: HashMap<Day, String>,
+}
+
+impl InputHashes {
+    /// Dehydrate hashes to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        fs::write(
+            paths().input_hashes(),
+            json::format_sorted(&JsonValue::from(self)),
+        )
+    }
+
+    /// Rehydrate hashes from a JSON file. If not present, returns no hashes.
+    pub fn read_from_file() -> Self {
+        fs::read_to_string(paths().input_hashes())
+            .map_err(|x| x.to_string())
+            .and_then(|s| InputHashes::try_from(s.as_str()))
+            .unwrap_or_default()
+    }
+
+    /// Whether an input differs from the one downloaded before. Unknown inputs never differ.
+    pub fn has_changed(&self, day: Day, input: &str) -> bool {
+        self.data.get(&day).is_some_and(|x| *x != hash(input))
+    }
+
+    pub fn set(&mut self, day: Day, input: &str) {
+        self.data.insert(day, hash(input));
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&InputHashes> for JsonValue {
+    fn from(value: &InputHashes) -> Self {
+        JsonValue::Object(
+            value
+                .data
+                .iter()
+                .map(|(day, hash)| (day.to_string(), JsonValue::String(hash.clone())))
+                .collect(),
+        )
+    }
+}
+
+impl TryFrom<&str> for InputHashes {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(value).or(Err("not valid JSON file."))?;
+        let map = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?;
+
+        let data = map
+            .iter()
+            .map(|(key, value)| {
+                let day = key
+                    .parse()
+                    .map_err(|_| format!("expected `{key}` to be a day."))?;
+                let hash = value
+                    .get::<String>()
+                    .ok_or(format!("expected the hash of day {key} to be a string."))?;
+                Ok((day, hash.clone()))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(InputHashes { data })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use tinyjson::JsonValue;
+
+    use super::{hash, validate, InputHashes, InvalidInput};
+    use crate::day;
+
+    #[test]
+    fn validates_inputs() {
+        assert_eq!(validate("3   4\n4   3\n"), Ok(()));
+        assert_eq!(validate("<{([{{}}[<[[[<>{}]]]>[]]\n"), Ok(()));
+        assert_eq!(validate(" \n"), Err(InvalidInput::Empty));
+        assert_eq!(
+            validate("Please don't repeatedly request this endpoint before it unlocks! The calendar countdown is synchronized with the server time."),
+            Err(InvalidInput::Locked)
+        );
+        assert_eq!(
+            validate("Puzzle inputs differ by user.  Please log in to get your puzzle input.\n"),
+            Err(InvalidInput::LoginPage)
+        );
+        assert_eq!(
+            validate("<!DOCTYPE html>\n<html><body>[<a href=\"/2024/auth/login\">Log In</a>]</body></html>"),
+            Err(InvalidInput::LoginPage)
+        );
+        assert_eq!(
+            validate("<!DOCTYPE html>\n<html><body>Oops</body></html>"),
+            Err(InvalidInput::Html)
+        );
+    }
+
+    #[test]
+    fn hashes_inputs() {
+        assert_eq!(hash(""), "cbf29ce484222325");
+        assert_eq!(hash("a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn tracks_changed_inputs() {
+        let mut hashes = InputHashes::default();
+        assert!(!hashes.has_changed(day!(1), "1\n"));
+
+        hashes.set(day!(1), "1\n");
+        assert!(!hashes.has_changed(day!(1), "1\n"));
+        assert!(hashes.has_changed(day!(1), "2\n"));
+
+        let json = JsonValue::from(&hashes).stringify().unwrap();
+        assert_eq!(InputHashes::try_from(json.as_str()), Ok(hashes));
+    }
+}
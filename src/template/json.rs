@@ -0,0 +1,120 @@
+/// Serializes JSON with object keys in sorted order.
+///
+/// `tinyjson` stores objects in a `HashMap`, so its output orders keys differently on every run.
+/// Files that are checked in use this instead, which keeps their diffs small.
+use tinyjson::JsonValue;
+
+/// Formats a value with two spaces of indentation, like [`JsonValue::format`].
+pub fn format_sorted(value: &JsonValue) -> String {
+    let mut out = String::new();
+    write(value, Some(0), &mut out);
+    out
+}
+
+/// Formats a value on a single line, like [`JsonValue::stringify`].
+pub fn stringify_sorted(value: &JsonValue) -> String {
+    let mut out = String::new();
+    write(value, None, &mut out);
+    out
+}
+
+fn write(value: &JsonValue, indent: Option<usize>, out: &mut String) {
+    let inner = indent.map(|x| x + 2);
+    let newline = |out: &mut String, indent: Option<usize>| {
+        if let Some(indent) = indent {
+            out.push('\n');
+            out.push_str(&" ".repeat(indent));
+        }
+    };
+
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, inner);
+                out.push_str(&leaf(&JsonValue::String(key.clone())));
+                out.push(':');
+                if indent.is_some() {
+                    out.push(' ');
+                }
+                write(&map[key], inner, out);
+            }
+            newline(out, indent);
+            out.push('}');
+        }
+        JsonValue::Array(items) if !items.is_empty() => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, inner);
+                write(item, inner, out);
+            }
+            newline(out, indent);
+            out.push(']');
+        }
+        x => out.push_str(&leaf(x)),
+    }
+}
+
+fn leaf(value: &JsonValue) -> String {
+    value
+        .stringify()
+        .expect("JSON files only contain finite numbers")
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::str::FromStr;
+
+    use tinyjson::JsonValue;
+
+    use super::{format_sorted, stringify_sorted};
+
+    #[test]
+    fn sorts_keys() {
+        let value = JsonValue::from_str(
+            r#"{ "b": 1, "a": [true, null, "x\"y"], "c": { "z": {}, "y": [] } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            stringify_sorted(&value),
+            r#"{"a":[true,null,"x\"y"],"b":1,"c":{"y":[],"z":{}}}"#
+        );
+
+        assert_eq!(
+            format_sorted(&value),
+            [
+                "{",
+                r#"  "a": ["#,
+                "    true,",
+                "    null,",
+                r#"    "x\"y""#,
+                "  ],",
+                r#"  "b": 1,"#,
+                r#"  "c": {"#,
+                r#"    "y": [],"#,
+                r#"    "z": {}"#,
+                "  }",
+                "}",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn roundtrips_values() {
+        let value = JsonValue::from_str(r#"{ "b": [1, 2.5], "a": { "c": "d" } }"#).unwrap();
+        assert_eq!(JsonValue::from_str(&format_sorted(&value)).unwrap(), value);
+    }
+}
@@ -0,0 +1,266 @@
+/// Lightweight heap metrics for solution parts.
+///
+/// With the `count-alloc` feature, a global allocator counts the allocations of the process.
+/// `run_part` uses it to report allocation count, bytes allocated and peak live bytes of a part.
+/// The counters are process-wide, so the numbers are approximate: they include the allocations of
+/// threads the part spawns, e.g. for rayon, but also of any other thread running at the same time.
+use std::{collections::HashMap, fmt::Display};
+use tinyjson::JsonValue;
+
+/// Heap usage of a single execution of a phase.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Memory {
+    pub allocations: u64,
+    /// Total bytes allocated, including memory that was freed again.
+    pub bytes: u64,
+    /// Maximum bytes live at the same time, on top of what was live beforehand.
+    pub peak: u64,
+}
+
+impl Memory {
+    /// Combines the usage of several phases: counts are added up, the peak is the largest peak.
+    pub fn combine(&self, other: &Self) -> Self {
+        Self {
+            allocations: self.allocations + other.allocations,
+            bytes: self.bytes + other.bytes,
+            peak: self.peak.max(other.peak),
+        }
+    }
+}
+
+impl Display for Memory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocations · {} allocated · {} peak",
+            self.allocations,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+/// Formats a byte count with binary units, e.g. `1.5 KiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Run `func` and measure its heap usage. Returns [`None`] for the usage if allocations are not counted.
+pub fn measure<T>(func: impl FnOnce() -> T) -> (T, Option<Memory>) {
+    #[cfg(feature = "count-alloc")]
+    {
+        let start = counter::snapshot();
+        let result = func();
+        (result, Some(counter::since(&start)))
+    }
+
+    #[cfg(not(feature = "count-alloc"))]
+    {
+        (func(), None)
+    }
+}
+
+#[cfg(feature = "count-alloc")]
+mod counter {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::Memory;
+
+    static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+    static BYTES: AtomicU64 = AtomicU64::new(0);
+    static LIVE: AtomicU64 = AtomicU64::new(0);
+    static PEAK: AtomicU64 = AtomicU64::new(0);
+
+    /// Forwards to the system allocator, counting every allocation.
+    struct CountingAlloc;
+
+    #[global_allocator]
+    static ALLOC: CountingAlloc = CountingAlloc;
+
+    fn record_alloc(size: u64) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(size: u64) {
+        LIVE.fetch_sub(size, Ordering::Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size() as u64);
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size() as u64);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            record_dealloc(layout.size() as u64);
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                record_dealloc(layout.size() as u64);
+                record_alloc(new_size as u64);
+            }
+            new_ptr
+        }
+    }
+
+    pub struct Snapshot {
+        allocations: u64,
+        bytes: u64,
+        live: u64,
+    }
+
+    /// Captures the counters, and resets the peak to the bytes that are currently live.
+    pub fn snapshot() -> Snapshot {
+        let live = LIVE.load(Ordering::Relaxed);
+        PEAK.store(live, Ordering::Relaxed);
+
+        Snapshot {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            bytes: BYTES.load(Ordering::Relaxed),
+            live,
+        }
+    }
+
+    pub fn since(start: &Snapshot) -> Memory {
+        Memory {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed) - start.allocations,
+            bytes: BYTES.load(Ordering::Relaxed) - start.bytes,
+            peak: PEAK.load(Ordering::Relaxed).saturating_sub(start.live),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Memory> for JsonValue {
+    fn from(value: &Memory) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "allocations".into(),
+            JsonValue::Number(value.allocations as f64),
+        );
+        map.insert("bytes".into(), JsonValue::Number(value.bytes as f64));
+        map.insert("peak".into(), JsonValue::Number(value.peak as f64));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Memory {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected memory to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .map(|x| x as u64)
+                .ok_or(format!("Expected memory.{key} to be a number."))
+        };
+
+        Ok(Memory {
+            allocations: number("allocations")?,
+            bytes: number("bytes")?,
+            peak: number("peak")?,
+        })
+    }
+}
+
+/// Serializes an optional memory usage, using `null` if it was not measured.
+pub fn to_json(memory: Option<&Memory>) -> JsonValue {
+    memory.map_or(JsonValue::Null, JsonValue::from)
+}
+
+/// Parses an optional memory usage. A missing key or `null` mean that it was not measured.
+pub fn from_json(value: Option<&JsonValue>) -> Result<Option<Memory>, String> {
+    match value {
+        Some(v) if !v.is_null() => Memory::try_from(v).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_bytes, from_json, to_json, Memory};
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn combines_memory() {
+        let a = Memory {
+            allocations: 2,
+            bytes: 100,
+            peak: 80,
+        };
+        let b = Memory {
+            allocations: 3,
+            bytes: 50,
+            peak: 50,
+        };
+        assert_eq!(
+            a.combine(&b),
+            Memory {
+                allocations: 5,
+                bytes: 150,
+                peak: 80
+            }
+        );
+    }
+
+    #[test]
+    fn roundtrips_memory() {
+        let memory = Memory {
+            allocations: 12,
+            bytes: 4096,
+            peak: 1024,
+        };
+        assert_eq!(
+            from_json(Some(&to_json(Some(&memory)))).unwrap(),
+            Some(memory)
+        );
+        assert_eq!(from_json(Some(&to_json(None))).unwrap(), None);
+        assert_eq!(from_json(None).unwrap(), None);
+    }
+}
@@ -1,37 +1,77 @@
-use std::{env, fs};
+use std::{env, fs, panic::Location};
 
-pub mod aoc_cli;
+pub mod aoc_client;
 pub mod commands;
+pub mod registry;
 pub mod runner;
 
+pub use bench_config::*;
 pub use day::*;
+pub use export::Export;
+pub use paths::{bin_year, select_year};
+pub use run_multi::RunOptions;
+pub use timings::MergeStrategy;
 
+mod answers;
+mod bench_config;
+mod chart;
+mod compare;
 mod day;
+mod environment;
+mod examples;
+mod export;
+mod history;
+mod html;
+mod inputs;
+mod json;
+mod memory;
+mod paths;
+mod protocol;
+mod readme;
 mod readme_benchmarks;
+mod readme_stars;
 mod run_multi;
+mod stars;
+mod stats;
+mod submissions;
 mod timings;
 
+#[cfg(all(feature = "dhat-heap", feature = "count-alloc"))]
+compile_error!("the `dhat-heap` and `count-alloc` features both install a global allocator, enable only one of them.");
+
+#[cfg(feature = "dhat-heap")]
+#[global_allocator]
+static ALLOC: dhat::Alloc = dhat::Alloc;
+
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
+pub const ANSI_RED: &str = "\x1b[31m";
+pub const ANSI_GREEN: &str = "\x1b[32m";
+
+/// The paths of the calling solution, namespaced by its year if it is named like `2024_05.rs`.
+#[track_caller]
+fn caller_paths() -> paths::Paths {
+    let year = bin_year(Location::caller().file()).or_else(paths::selected_year);
+    paths::paths_for_year(year)
+}
 
 /// Helper function that reads a text file to a string.
 #[must_use]
+#[track_caller]
 pub fn read_file(folder: &str, day: Day) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = cwd.join(caller_paths().day_file(folder, day));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
+#[track_caller]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
-        .join(format!("{day}-{part}.txt"));
+    let filepath = cwd.join(caller_paths().day_part_file(folder, day, part));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
@@ -39,6 +79,10 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// A `parse: <function>` parameter can be passed to parse the input once. The parsed value is passed
+/// to both parts by reference and the time spent parsing is reported separately,
+/// e.g. `solution!(8, parse: parse_input)` or `solution!(8, parse: parse_input, 1)`.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -50,19 +94,51 @@ macro_rules! solution {
     ($day:expr, 2) => {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
+    ($day:expr, parse: $parse:expr) => {
+        $crate::solution!(@impl_parsed $day, $parse, [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, parse: $parse:expr, 1) => {
+        $crate::solution!(@impl_parsed $day, $parse, [part_one, 1]);
+    };
+    ($day:expr, parse: $parse:expr, 2) => {
+        $crate::solution!(@impl_parsed $day, $parse, [part_two, 2]);
+    };
 
     (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+        $crate::solution!(@day $day);
+
+        // Called by `main` and the solution registry, not part of the public API.
+        #[doc(hidden)]
+        pub fn __solve(input: &str) {
+            use $crate::template::runner::*;
+            $( run_part($func, input, DAY, $part); )*
+        }
+    };
+
+    (@impl_parsed $day:expr, $parse:expr, $( [$func:expr, $part:expr] )*) => {
+        $crate::solution!(@day $day);
+
+        // Called by `main` and the solution registry, not part of the public API.
+        #[doc(hidden)]
+        pub fn __solve(input: &str) {
+            use $crate::template::runner::*;
+            if let Some(parsed) = run_parse($parse, input, DAY) {
+                $( run_part($func, &parsed, DAY, $part); )*
+            }
+        }
+    };
+
+    (@day $day:expr) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
-        #[cfg(feature = "dhat-heap")]
-        #[global_allocator]
-        static ALLOC: dhat::Alloc = dhat::Alloc;
-
         fn main() {
-            use $crate::template::runner::*;
+            // solutions named like `2024_05.rs` read and store the data of their year.
+            if let Some(year) = $crate::template::bin_year(file!()) {
+                $crate::template::select_year(year);
+            }
             let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            __solve(&input);
         }
     };
 }
@@ -0,0 +1,345 @@
+/// Locations of the files the template reads and writes.
+/// They are read from the `[paths]` table of `aoc.toml` and from environment variables,
+/// which take precedence. Relative paths are resolved against the working directory.
+///
+/// When a year is selected, data, timings and chart are namespaced by it, e.g. `data/2024/inputs/05.txt`,
+/// and solutions are expected in `src/bin/2024_05.rs`.
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Component, Path, PathBuf},
+    process,
+    sync::OnceLock,
+};
+
+use crate::template::Day;
+
+static CONFIG_FILE_PATH: &str = "aoc.toml";
+
+/// Keys of the `[paths]` table and the environment variables that override them.
+static KEYS: [(&str, &str); 4] = [
+    ("data", "AOC_DATA_DIR"),
+    ("timings", "AOC_TIMINGS_FILE"),
+    ("readme", "AOC_README"),
+    ("chart", "AOC_CHART_FILE"),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Paths {
+    /// The year the paths are namespaced by.
+    pub year: Option<u16>,
+    /// Directory of the inputs, examples, puzzles and stored data.
+    pub data: PathBuf,
+    pub timings: PathBuf,
+    pub readme: PathBuf,
+    pub chart: PathBuf,
+}
+
+impl Paths {
+    /// Resolve the paths from the parsed `[paths]` table and a lookup of environment variables.
+    fn resolve(config: &HashMap<String, String>, var: impl Fn(&str) -> Option<String>) -> Self {
+        let get = |key: &str| {
+            KEYS.iter()
+                .find(|(k, _)| *k == key)
+                .and_then(|(_, name)| var(name))
+                .or_else(|| config.get(key).cloned())
+                .map(PathBuf::from)
+        };
+
+        let data = get("data").unwrap_or_else(|| "data".into());
+
+        Self {
+            year: None,
+            timings: get("timings").unwrap_or_else(|| data.join("timings.json")),
+            readme: get("readme").unwrap_or_else(|| "README.md".into()),
+            chart: get("chart").unwrap_or_else(|| Path::new(".assets").join("benchmarks.svg")),
+            data,
+        }
+    }
+
+    /// Namespace the paths by a year. The year becomes a directory next to each file.
+    pub fn for_year(&self, year: Option<u16>) -> Self {
+        let Some(year) = year else {
+            return self.clone();
+        };
+
+        let namespace = |path: &Path| match (path.parent(), path.file_name()) {
+            (Some(dir), Some(name)) => dir.join(year.to_string()).join(name),
+            _ => path.join(year.to_string()),
+        };
+
+        Self {
+            year: Some(year),
+            data: self.data.join(year.to_string()),
+            timings: namespace(&self.timings),
+            readme: self.readme.clone(),
+            chart: namespace(&self.chart),
+        }
+    }
+
+    /// The name of the solution binary of a day, e.g. `05` or `2024_05`.
+    pub fn bin(&self, day: Day) -> String {
+        match self.year {
+            Some(year) => format!("{year}_{day}"),
+            None => day.to_string(),
+        }
+    }
+
+    /// The source file of the solution of a day, e.g. `src/bin/05.rs`.
+    pub fn bin_path(&self, day: Day) -> String {
+        format!("src/bin/{}.rs", self.bin(day))
+    }
+
+    /// A text file of a day in a folder of the data directory, e.g. `data/inputs/01.txt`.
+    pub fn day_file(&self, folder: &str, day: Day) -> PathBuf {
+        self.data.join(folder).join(format!("{day}.txt"))
+    }
+
+    /// A text file of one part of a day, e.g. `data/examples/01-2.txt`.
+    pub fn day_part_file(&self, folder: &str, day: Day, part: u8) -> PathBuf {
+        self.data.join(folder).join(format!("{day}-{part}.txt"))
+    }
+
+    pub fn input(&self, day: Day) -> PathBuf {
+        self.day_file("inputs", day)
+    }
+
+    pub fn example(&self, day: Day) -> PathBuf {
+        self.day_file("examples", day)
+    }
+
+    pub fn puzzle(&self, day: Day) -> PathBuf {
+        self.data.join("puzzles").join(format!("{day}.md"))
+    }
+
+    pub fn history(&self) -> PathBuf {
+        self.data.join("timings_history.jsonl")
+    }
+
+    pub fn stars(&self) -> PathBuf {
+        self.data.join("stars.json")
+    }
+
+    pub fn input_hashes(&self) -> PathBuf {
+        self.data.join("input_hashes.json")
+    }
+
+    pub fn answers(&self) -> PathBuf {
+        self.data.join("answers.json")
+    }
+
+    pub fn submissions(&self, day: Day) -> PathBuf {
+        self.data.join("submissions").join(format!("{day}.jsonl"))
+    }
+
+    /// The chart as linked from the readme, e.g. `./.assets/benchmarks.svg`.
+    pub fn chart_link(&self) -> String {
+        let readme_dir = self.readme.parent().map(normalize).unwrap_or_default();
+        let chart = normalize(&self.chart);
+
+        match chart.strip_prefix(&readme_dir) {
+            Ok(relative) => format!("./{}", to_slashes(relative)),
+            Err(_) => to_slashes(&chart),
+        }
+    }
+}
+
+/// Drop `.` components, so that `./README.md` and `README.md` compare equal.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+fn to_slashes(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Parse the `[paths]` table of an `aoc.toml`. Only string values are supported, other tables are ignored.
+fn parse_config(s: &str) -> Result<HashMap<String, String>, String> {
+    let mut config = HashMap::new();
+    let mut table = "";
+
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+            table = name.trim();
+            continue;
+        }
+
+        // other tables may hold any toml, e.g. the settings of other tools.
+        if table != "paths" {
+            continue;
+        }
+
+        let value = line
+            .split_once('=')
+            .and_then(|(key, value)| {
+                let value = value.trim();
+                let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+                let (value, rest) = value[1..].split_once(quote)?;
+                let rest = rest.trim();
+                (rest.is_empty() || rest.starts_with('#')).then(|| (key.trim(), value))
+            })
+            .ok_or_else(|| format!("line {}: expected `key = \"value\"`.", i + 1))?;
+
+        if !KEYS.iter().any(|(key, _)| *key == value.0) {
+            return Err(format!(
+                "line {}: unknown path `{}`, expected one of: {}.",
+                i + 1,
+                value.0,
+                KEYS.map(|(key, _)| key).join(", ")
+            ));
+        }
+        config.insert(value.0.to_string(), value.1.to_string());
+    }
+
+    Ok(config)
+}
+
+static YEAR: OnceLock<u16> = OnceLock::new();
+
+/// Select the year that paths are namespaced by, for the rest of the process.
+pub fn select_year(year: u16) {
+    let _ = YEAR.set(year);
+}
+
+/// The year passed via `--year`, or the year of the running solution.
+pub fn selected_year() -> Option<u16> {
+    YEAR.get().copied()
+}
+
+/// The year of a solution file named like `2024_05.rs`.
+pub fn bin_year(file: &str) -> Option<u16> {
+    let stem = Path::new(file).file_stem()?.to_str()?;
+    let (year, day) = stem.split_once('_')?;
+
+    let is_digits = |x: &str, len: usize| x.len() == len && x.chars().all(|c| c.is_ascii_digit());
+    (is_digits(year, 4) && is_digits(day, 2)).then(|| year.parse().ok())?
+}
+
+/// The configured paths, namespaced by the selected year.
+pub fn paths() -> Paths {
+    paths_for_year(selected_year())
+}
+
+/// The configured paths, namespaced by the given year.
+pub fn paths_for_year(year: Option<u16>) -> Paths {
+    base().for_year(year)
+}
+
+/// The configured paths. `aoc.toml` and the environment are read once.
+/// Exits if `aoc.toml` can not be parsed, rather than reading and writing files in the wrong place.
+fn base() -> &'static Paths {
+    static PATHS: OnceLock<Paths> = OnceLock::new();
+
+    PATHS.get_or_init(|| {
+        let config = match fs::read_to_string(CONFIG_FILE_PATH) {
+            Ok(s) => parse_config(&s).unwrap_or_else(|e| {
+                eprintln!("Failed to parse {CONFIG_FILE_PATH}: {e}");
+                process::exit(1);
+            }),
+            Err(_) => HashMap::new(),
+        };
+
+        Paths::resolve(&config, |name| {
+            env::var(name).ok().filter(|x| !x.is_empty())
+        })
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use super::{bin_year, parse_config, Paths};
+    use crate::day;
+
+    fn resolve(config: &str, vars: &[(&str, &str)]) -> Paths {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        Paths::resolve(&parse_config(config).unwrap(), |name| {
+            vars.get(name).map(ToString::to_string)
+        })
+    }
+
+    #[test]
+    fn resolves_defaults() {
+        let paths = resolve("", &[]);
+        assert_eq!(paths.input(day!(1)), PathBuf::from("data/inputs/01.txt"));
+        assert_eq!(paths.puzzle(day!(1)), PathBuf::from("data/puzzles/01.md"));
+        assert_eq!(paths.timings, PathBuf::from("data/timings.json"));
+        assert_eq!(paths.readme, PathBuf::from("README.md"));
+        assert_eq!(paths.chart_link(), "./.assets/benchmarks.svg");
+    }
+
+    #[test]
+    fn resolves_config_and_env() {
+        let config = [
+            "# paths of this checkout",
+            "[paths]",
+            "data = \"aoc/data\" # shared data",
+            "readme = 'docs/README.md'",
+            "chart = \"./docs/chart.svg\"",
+            "",
+            "[other]",
+            "foo = \"bar\"",
+            "threads = 4",
+            "list = [",
+            "  \"a\",",
+            "]",
+        ]
+        .join("\n");
+
+        let paths = resolve(&config, &[]);
+        assert_eq!(paths.stars(), PathBuf::from("aoc/data/stars.json"));
+        assert_eq!(paths.timings, PathBuf::from("aoc/data/timings.json"));
+        assert_eq!(paths.chart_link(), "./chart.svg");
+
+        let paths = resolve(&config, &[("AOC_TIMINGS_FILE", "/tmp/timings.json")]);
+        assert_eq!(paths.data, PathBuf::from("aoc/data"));
+        assert_eq!(paths.timings, PathBuf::from("/tmp/timings.json"));
+    }
+
+    #[test]
+    fn namespaces_by_year() {
+        let paths = resolve("", &[]).for_year(Some(2023));
+        assert_eq!(
+            paths.input(day!(5)),
+            PathBuf::from("data/2023/inputs/05.txt")
+        );
+        assert_eq!(paths.timings, PathBuf::from("data/2023/timings.json"));
+        assert_eq!(paths.readme, PathBuf::from("README.md"));
+        assert_eq!(paths.chart_link(), "./.assets/2023/benchmarks.svg");
+        assert_eq!(paths.bin(day!(5)), "2023_05");
+        assert_eq!(paths.bin_path(day!(5)), "src/bin/2023_05.rs");
+
+        let paths = resolve("", &[("AOC_TIMINGS_FILE", "timings.json")]).for_year(Some(2023));
+        assert_eq!(paths.timings, PathBuf::from("2023/timings.json"));
+        assert_eq!(resolve("", &[]).bin(day!(5)), "05");
+    }
+
+    #[test]
+    fn parses_bin_years() {
+        assert_eq!(bin_year("src/bin/2024_05.rs"), Some(2024));
+        assert_eq!(bin_year("/abs/src/bin/2015_25.rs"), Some(2015));
+        assert_eq!(bin_year("src/bin/05.rs"), None);
+        assert_eq!(bin_year("src/bin/2024_5.rs"), None);
+        assert_eq!(bin_year("src/template/mod.rs"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_config() {
+        assert!(parse_config("[paths]\ndata = data").is_err());
+        assert!(parse_config("[paths]\ndata = \"data").is_err());
+        assert!(parse_config("[paths]\ninputs = \"data\"").is_err());
+    }
+}
@@ -0,0 +1,417 @@
+/// Machine-readable records that solution binaries emit when invoked with `--format json`.
+/// Every record is written as a single line of JSON to stdout, which lets `run_multi` consume
+/// results without scraping the human-readable output.
+use std::{collections::HashMap, env, fmt::Display, str::FromStr, time::Duration};
+use tinyjson::JsonValue;
+
+use crate::template::answers::Check;
+use crate::template::memory::{self, Memory};
+use crate::template::stats::Stats;
+use crate::template::Day;
+
+/// A timed step of a solution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Part(u8),
+}
+
+impl Phase {
+    /// The key identifying the phase in a serialized record.
+    pub fn key(&self) -> String {
+        match self {
+            Phase::Parse => "parse".into(),
+            Phase::Part(part) => part.to_string(),
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "parse" => Some(Phase::Parse),
+            x => x.parse().ok().map(Phase::Part),
+        }
+    }
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "Parse"),
+            Phase::Part(part) => write!(f, "Part {part}"),
+        }
+    }
+}
+
+/// Outcome of running a phase.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The phase ran to completion and, for parts, produced an answer.
+    Ok,
+    /// The part returned `None`.
+    Unsolved,
+    /// The part panicked, the panic message is attached.
+    Panicked(String),
+    /// The part did not finish within the `--timeout` limit.
+    TimedOut,
+}
+
+impl Status {
+    fn key(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Unsolved => "unsolved",
+            Status::Panicked(_) => "panicked",
+            Status::TimedOut => "timed_out",
+        }
+    }
+
+    fn from_key(key: &str, error: Option<&String>) -> Option<Self> {
+        match key {
+            "ok" => Some(Status::Ok),
+            "unsolved" => Some(Status::Unsolved),
+            "panicked" => error.cloned().map(Status::Panicked),
+            "timed_out" => Some(Status::TimedOut),
+            _ => None,
+        }
+    }
+
+    /// Describes why the phase failed, or [`None`] if it did not fail.
+    pub fn failure(&self) -> Option<String> {
+        match self {
+            Status::Panicked(msg) => Some(format!("panicked: {msg}")),
+            Status::TimedOut => Some("timed out".into()),
+            Status::Ok | Status::Unsolved => None,
+        }
+    }
+}
+
+/// The result of running a single phase of a day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub day: Day,
+    pub phase: Phase,
+    pub status: Status,
+    pub answer: Option<String>,
+    /// Result of verifying the answer against the known answers.
+    pub check: Check,
+    pub stats: Stats,
+    /// Heap usage of the first execution, if allocations were counted.
+    pub memory: Option<Memory>,
+}
+
+impl Record {
+    /// Whether the solution binary was asked to emit records instead of human-readable output.
+    pub fn is_enabled() -> bool {
+        let args: Vec<String> = env::args().collect();
+        args.windows(2)
+            .any(|w| w[0] == "--format" && w[1] == "json")
+    }
+
+    /// Serializes the record to a single line of JSON.
+    pub fn to_line(&self) -> String {
+        JsonValue::from(self)
+            .stringify()
+            .expect("records only contain finite numbers")
+    }
+}
+
+/// A line emitted by a process that runs solutions with `--format json`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    /// The solution registry started running a day. Lines up to the next start belong to this day.
+    Start(Day),
+    Record(Box<Record>),
+}
+
+impl Message {
+    /// Serializes the message to a single line of JSON.
+    pub fn to_line(&self) -> String {
+        match self {
+            Message::Start(day) => {
+                let mut map: HashMap<String, JsonValue> = HashMap::new();
+                map.insert("event".into(), JsonValue::String("start".into()));
+                map.insert("day".into(), JsonValue::String(day.to_string()));
+                JsonValue::Object(map)
+                    .stringify()
+                    .expect("start events only contain strings")
+            }
+            Message::Record(record) => record.to_line(),
+        }
+    }
+
+    /// Parses a line written by [`Message::to_line`].
+    pub fn from_line(line: &str) -> Result<Self, String> {
+        let json = JsonValue::from_str(line).or(Err("not valid JSON."))?;
+
+        let event = json
+            .get::<HashMap<String, JsonValue>>()
+            .and_then(|map| map.get("event"))
+            .and_then(|v| v.get::<String>());
+
+        match event.map(String::as_str) {
+            None => Record::try_from(&json).map(|x| Message::Record(Box::new(x))),
+            Some("start") => json
+                .get::<HashMap<String, JsonValue>>()
+                .and_then(|map| map.get("day"))
+                .and_then(|v| v.get::<String>())
+                .and_then(|day| Day::from_str(day).ok())
+                .map(Message::Start)
+                .ok_or("Expected start event to have a day.".into()),
+            Some(x) => Err(format!("Unknown event `{x}`.")),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn nanos(duration: Duration) -> JsonValue {
+    JsonValue::Number(duration.as_nanos() as f64)
+}
+
+impl From<&Record> for JsonValue {
+    fn from(value: &Record) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part".into(), JsonValue::String(value.phase.key()));
+        map.insert(
+            "status".into(),
+            JsonValue::String(value.status.key().into()),
+        );
+        if let Status::Panicked(msg) = &value.status {
+            map.insert("error".into(), JsonValue::String(msg.clone()));
+        }
+        map.insert(
+            "answer".into(),
+            match &value.answer {
+                Some(x) => JsonValue::String(x.clone()),
+                None => JsonValue::Null,
+            },
+        );
+        let (check, expected) = match &value.check {
+            Check::Correct => ("correct", JsonValue::Null),
+            Check::Incorrect(expected) => ("incorrect", JsonValue::String(expected.clone())),
+            Check::Unknown => ("unknown", JsonValue::Null),
+        };
+        map.insert("check".into(), JsonValue::String(check.into()));
+        map.insert("expected".into(), expected);
+
+        map.insert("nanos".into(), nanos(value.stats.mean));
+        map.insert(
+            "samples".into(),
+            JsonValue::Number(value.stats.samples as f64),
+        );
+
+        let mut stats: HashMap<String, JsonValue> = HashMap::new();
+        stats.insert("median".into(), nanos(value.stats.median));
+        stats.insert("min".into(), nanos(value.stats.min));
+        stats.insert("max".into(), nanos(value.stats.max));
+        stats.insert("std_dev".into(), nanos(value.stats.std_dev));
+        stats.insert("p95".into(), nanos(value.stats.p95));
+        stats.insert("p99".into(), nanos(value.stats.p99));
+        stats.insert(
+            "outliers".into(),
+            JsonValue::Number(value.stats.outliers as f64),
+        );
+        map.insert("stats".into(), JsonValue::Object(stats));
+        map.insert("memory".into(), memory::to_json(value.memory.as_ref()));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Record {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected record to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected record.day to be a Day struct.")?;
+
+        let phase = json
+            .get("part")
+            .and_then(|v| v.get::<String>())
+            .and_then(|x| Phase::from_key(x))
+            .ok_or("Expected record.part to be `parse` or a part number.")?;
+
+        let error = json.get("error").and_then(|v| v.get::<String>());
+        let status = json
+            .get("status")
+            .and_then(|v| v.get::<String>())
+            .and_then(|x| Status::from_key(x, error))
+            .ok_or("Expected record.status to be a known status.")?;
+
+        let answer = json
+            .get("answer")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected record.answer to be null or string.")?;
+
+        let expected = json.get("expected").and_then(|v| v.get::<String>());
+        let check = match json.get("check").and_then(|v| v.get::<String>()) {
+            Some(x) if x == "correct" => Check::Correct,
+            Some(x) if x == "incorrect" => Check::Incorrect(
+                expected
+                    .ok_or("Expected record.expected to be a string.")?
+                    .clone(),
+            ),
+            Some(x) if x == "unknown" => Check::Unknown,
+            _ => return Err("Expected record.check to be a known check.".into()),
+        };
+
+        let number = |map: &HashMap<String, JsonValue>, key: &str| {
+            map.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected record.{key} to be a number."))
+        };
+        let duration = |map: &HashMap<String, JsonValue>, key: &str| {
+            number(map, key).map(|x| Duration::from_nanos(x as u64))
+        };
+
+        let stats = json
+            .get("stats")
+            .and_then(|v| v.get::<HashMap<String, JsonValue>>())
+            .ok_or("Expected record.stats to be a JSON object.")?;
+
+        Ok(Record {
+            day,
+            phase,
+            status,
+            answer: answer.cloned(),
+            check,
+            stats: Stats {
+                samples: number(json, "samples")? as u128,
+                outliers: number(stats, "outliers")? as u128,
+                mean: duration(json, "nanos")?,
+                median: duration(stats, "median")?,
+                min: duration(stats, "min")?,
+                max: duration(stats, "max")?,
+                std_dev: duration(stats, "std_dev")?,
+                p95: duration(stats, "p95")?,
+                p99: duration(stats, "p99")?,
+            },
+            memory: memory::from_json(json.get("memory"))?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Memory, Message, Phase, Record, Status};
+    use crate::{
+        day,
+        template::{answers::Check, stats::Stats},
+    };
+
+    fn parse_record(line: &str) -> Result<Record, String> {
+        match Message::from_line(line)? {
+            Message::Record(record) => Ok(*record),
+            Message::Start(_) => Err("expected a record.".into()),
+        }
+    }
+
+    fn get_mock_record(answer: Option<&str>) -> Record {
+        Record {
+            day: day!(6),
+            phase: Phase::Part(2),
+            status: if answer.is_some() {
+                Status::Ok
+            } else {
+                Status::Unsolved
+            },
+            answer: answer.map(Into::into),
+            check: Check::Unknown,
+            stats: Stats::single(Duration::from_nanos(74130)),
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn roundtrips_records() {
+        let record = get_mock_record(Some("42"));
+        let line = record.to_line();
+        assert!(!line.contains('\n'));
+        assert_eq!(parse_record(&line).unwrap(), record);
+    }
+
+    #[test]
+    fn roundtrips_answers_with_patterns() {
+        let record = get_mock_record(Some("@ @ ( ) ms (2s @ 5 samples)\n#..#\n\"quoted\""));
+        let parsed = parse_record(&record.to_line()).unwrap();
+        assert_eq!(parsed.answer, record.answer);
+    }
+
+    #[test]
+    fn roundtrips_missing_answers() {
+        let record = get_mock_record(None);
+        let parsed = parse_record(&record.to_line()).unwrap();
+        assert_eq!(parsed.answer, None);
+        assert_eq!(parsed.status, Status::Unsolved);
+    }
+
+    #[test]
+    fn roundtrips_parse_phase() {
+        let mut record = get_mock_record(None);
+        record.phase = Phase::Parse;
+        record.status = Status::Ok;
+        let parsed = parse_record(&record.to_line()).unwrap();
+        assert_eq!(parsed.phase, Phase::Parse);
+    }
+
+    #[test]
+    fn roundtrips_checks() {
+        let mut record = get_mock_record(Some("42"));
+        record.check = Check::Incorrect("41".into());
+        assert_eq!(parse_record(&record.to_line()).unwrap(), record);
+
+        record.check = Check::Correct;
+        assert_eq!(parse_record(&record.to_line()).unwrap(), record);
+    }
+
+    #[test]
+    fn roundtrips_failures() {
+        let mut record = get_mock_record(None);
+        record.status = Status::Panicked("index out of bounds: \"x\"".into());
+        assert_eq!(parse_record(&record.to_line()).unwrap(), record);
+
+        record.status = Status::TimedOut;
+        assert_eq!(parse_record(&record.to_line()).unwrap(), record);
+    }
+
+    #[test]
+    fn roundtrips_memory() {
+        let mut record = get_mock_record(Some("42"));
+        record.memory = Some(Memory {
+            allocations: 3,
+            bytes: 2048,
+            peak: 1024,
+        });
+        assert_eq!(parse_record(&record.to_line()).unwrap(), record);
+    }
+
+    #[test]
+    fn roundtrips_messages() {
+        let start = Message::Start(day!(9));
+        assert_eq!(Message::from_line(&start.to_line()).unwrap(), start);
+
+        let record = Message::Record(Box::new(get_mock_record(Some("42"))));
+        assert_eq!(Message::from_line(&record.to_line()).unwrap(), record);
+
+        assert!(Message::from_line(r#"{ "event": "stop", "day": "09" }"#).is_err());
+    }
+
+    #[test]
+    fn rejects_other_lines() {
+        assert!(parse_record("Part 1: 0 (74.13ns @ 100000 samples)").is_err());
+        assert!(parse_record(r#"{ "day": "01" }"#).is_err());
+    }
+}
@@ -0,0 +1,409 @@
+/// Sections of the readme that are generated from the stored data.
+/// A section is enclosed by `<!--- aoc:NAME --->` and `<!--- /aoc:NAME --->` markers.
+/// Sections of a year are named like `aoc:NAME:2024` and generated from the data of that year.
+use std::{fmt::Display, fs, io, ops::Range};
+
+use crate::template::aoc_client;
+use crate::template::chart;
+use crate::template::paths::{paths, selected_year};
+use crate::template::readme_benchmarks::{self, TableOptions};
+use crate::template::readme_stars;
+use crate::template::stars::Stars;
+use crate::template::timings::Timings;
+
+/// Markers used before sections were named. Both are replaced with named markers on update.
+static LEGACY_MARKERS: [(&str, &str); 2] = [
+    ("benchmarks", "<!--- benchmarking table --->"),
+    ("stars", "<!--- advent_readme_stars table --->"),
+];
+
+/// Generates the content of a section. Returns [`None`] if there is no data to fill it with,
+/// in which case the section is left as is.
+type Generator = fn(&Context) -> Option<String>;
+
+static GENERATORS: [(&str, Generator); 3] = [
+    ("benchmarks", readme_benchmarks::generate),
+    ("stars", readme_stars::generate),
+    ("status", generate_status),
+];
+
+#[derive(Debug)]
+pub enum Error {
+    Parser(String),
+    IO(io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parser(e) => write!(f, "{e}"),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+/// Data the sections are generated from.
+#[derive(Clone, Debug, Default)]
+pub struct Context {
+    pub timings: Timings,
+    pub stars: Stars,
+    pub year: Option<u16>,
+    pub table: TableOptions,
+}
+
+impl Context {
+    fn load(readme: &str) -> Result<Self, Error> {
+        Ok(Self {
+            timings: Timings::read_from_file().map_err(Error::Parser)?,
+            stars: readme_stars::load(readme).map_err(Error::Parser)?,
+            year: aoc_client::get_year(),
+            table: TableOptions::from_env(),
+        })
+    }
+}
+
+/// A section found in the readme.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Section {
+    name: String,
+    /// Byte range of the section, including its markers.
+    range: Range<usize>,
+    /// Byte range of the content between the markers.
+    content: Range<usize>,
+}
+
+fn start_marker(name: &str) -> String {
+    format!("<!--- aoc:{name} --->")
+}
+
+fn end_marker(name: &str) -> String {
+    format!("<!--- /aoc:{name} --->")
+}
+
+/// Positions of `pattern` at the start of a line, so that markers quoted in the text are ignored.
+fn find_markers<'a>(readme: &'a str, pattern: &'a str) -> impl Iterator<Item = usize> + 'a {
+    readme
+        .match_indices(pattern)
+        .map(|(i, _)| i)
+        .filter(|i| *i == 0 || readme.as_bytes()[i - 1] == b'\n')
+}
+
+/// Find all sections of the readme, ordered by their position.
+fn find_sections(readme: &str) -> Result<Vec<Section>, Error> {
+    let mut sections: Vec<Section> = vec![];
+
+    let mut pos = 0;
+    while let Some(offset) = find_markers(&readme[pos..], "<!--- aoc:").next() {
+        let start = pos + offset;
+        let name = readme[start..]
+            .strip_prefix("<!--- aoc:")
+            .and_then(|x| x.split_once(" --->"))
+            .map(|(name, _)| name)
+            .filter(|name| !name.contains('\n'))
+            .ok_or_else(|| Error::Parser(format!("Malformed marker at byte {start}.")))?;
+
+        let content_start = start + start_marker(name).len();
+        let end_marker = end_marker(name);
+        let content_end = find_markers(&readme[content_start..], &end_marker)
+            .next()
+            .map(|x| content_start + x)
+            .ok_or_else(|| {
+                Error::Parser(format!("Section `{name}` is not closed by `{end_marker}`."))
+            })?;
+
+        pos = content_end + end_marker.len();
+        sections.push(Section {
+            name: name.into(),
+            range: start..pos,
+            content: content_start..content_end,
+        });
+    }
+
+    for (name, marker) in LEGACY_MARKERS {
+        let matches: Vec<_> = find_markers(readme, marker).collect();
+        match matches[..] {
+            [] => {}
+            [start, end] => sections.push(Section {
+                name: name.into(),
+                range: start..end + marker.len(),
+                content: start + marker.len()..end,
+            }),
+            _ => {
+                return Err(Error::Parser(format!(
+                    "Expected a pair of `{marker}` markers, found {}.",
+                    matches.len()
+                )))
+            }
+        }
+    }
+
+    sections.sort_unstable_by_key(|s| s.range.start);
+    Ok(sections)
+}
+
+/// The name of a section of a year, e.g. `stars:2024`.
+pub fn section_name(name: &str, year: Option<u16>) -> String {
+    match year {
+        Some(year) => format!("{name}:{year}"),
+        None => name.into(),
+    }
+}
+
+/// Split a section name into the name of its generator and its year.
+fn split_section_name(name: &str) -> (&str, Option<u16>) {
+    match name
+        .rsplit_once(':')
+        .map(|(name, year)| (name, year.parse()))
+    {
+        Some((name, Ok(year))) => (name, Some(year)),
+        _ => (name, None),
+    }
+}
+
+/// The content of the first section with the given name.
+pub fn section_content<'a>(readme: &'a str, name: &str) -> Option<&'a str> {
+    find_sections(readme)
+        .ok()?
+        .into_iter()
+        .find(|s| s.name == name)
+        .map(|s| &readme[s.content])
+}
+
+/// Regenerate the sections of a year with the given names, or all sections of the year.
+/// Returns the names of the regenerated sections and of the sections without a generator.
+fn update_content(
+    readme: &mut String,
+    context: &Context,
+    names: Option<&[&str]>,
+    year: Option<u16>,
+) -> Result<(Vec<String>, Vec<String>), Error> {
+    let mut updated = vec![];
+    let mut unknown = vec![];
+
+    for section in find_sections(readme)?.into_iter().rev() {
+        let (name, section_year) = split_section_name(&section.name);
+        if section_year != year || names.is_some_and(|names| !names.contains(&name)) {
+            continue;
+        }
+
+        let Some((_, generate)) = GENERATORS.iter().find(|(x, _)| *x == name) else {
+            unknown.push(name.to_string());
+            continue;
+        };
+
+        // sections without data keep their content, but legacy markers are still replaced.
+        let section_text = match generate(context) {
+            Some(content) => {
+                updated.push(section.name.clone());
+                format!("\n{content}\n")
+            }
+            None => readme[section.content].to_string(),
+        };
+
+        readme.replace_range(
+            section.range,
+            &format!(
+                "{}{section_text}{}",
+                start_marker(&section.name),
+                end_marker(&section.name)
+            ),
+        );
+    }
+
+    updated.reverse();
+    unknown.reverse();
+    Ok((updated, unknown))
+}
+
+/// Regenerate the sections of the selected year with the given names, or all its sections, from the stored data.
+/// Returns the names of the regenerated sections. Sections without a generator are reported.
+pub fn update(names: Option<&[&str]>) -> Result<Vec<String>, Error> {
+    let mut readme = String::from_utf8_lossy(&fs::read(&paths().readme)?).to_string();
+    let mut context = Context::load(&readme)?;
+
+    if names.map_or(true, |names| names.contains(&"benchmarks"))
+        && chart::store_file(&context.timings)?
+    {
+        context.table.chart = Some(paths().chart_link());
+    }
+
+    let (updated, unknown) = update_content(&mut readme, &context, names, selected_year())?;
+
+    for name in unknown {
+        let known: Vec<&str> = GENERATORS.iter().map(|(name, _)| *name).collect();
+        eprintln!(
+            "Unknown readme section `{name}`, expected one of: {}.",
+            known.join(", ")
+        );
+    }
+
+    fs::write(&paths().readme, &readme)?;
+    Ok(updated)
+}
+
+/// A single line summarizing the progress.
+fn generate_status(context: &Context) -> Option<String> {
+    let mut parts = vec![format!("⭐ **{}** stars", context.stars.count())];
+
+    if !context.timings.data.is_empty() {
+        parts.push(format!(
+            "**{}** days benchmarked in **{:.2}ms**",
+            context.timings.data.len(),
+            context.timings.total_millis()
+        ));
+    }
+
+    Some(parts.join(" · "))
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{find_sections, section_content, section_name, update_content, Context};
+    use crate::{
+        day,
+        template::{
+            stars::Stars,
+            timings::{Timing, Timings},
+        },
+    };
+
+    fn get_mock_context() -> Context {
+        let mut stars = Stars::default();
+        stars.add(day!(1), 1);
+        stars.add(day!(1), 2);
+
+        Context {
+            timings: Timings {
+                data: vec![Timing {
+                    day: day!(1),
+                    parse: None,
+                    part_1: Some(Duration::from_millis(1).into()),
+                    part_2: Some(Duration::from_millis(2).into()),
+                    total_nanos: 3_000_000_f64,
+                    failures: vec![],
+                    memory: None,
+                }],
+                environment: None,
+            },
+            stars,
+            year: Some(2024),
+            ..Context::default()
+        }
+    }
+
+    #[test]
+    fn finds_sections() {
+        let readme = "a\n<!--- aoc:status --->\nold\n<!--- /aoc:status --->\nb\n<!--- aoc:foo ---><!--- /aoc:foo --->";
+        let sections = find_sections(readme).unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].name, "status");
+        assert_eq!(&readme[sections[0].content.clone()], "\nold\n");
+        assert_eq!(sections[1].name, "foo");
+        assert_eq!(section_content(readme, "foo"), Some(""));
+    }
+
+    #[test]
+    fn ignores_quoted_markers() {
+        let readme = "`<!--- aoc:NAME --->`\n<!--- aoc:status ---> <!--- /aoc:status --->\n<!--- /aoc:status --->";
+        let sections = find_sections(readme).unwrap();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(
+            section_content(readme, "status"),
+            Some(" <!--- /aoc:status --->\n")
+        );
+    }
+
+    #[test]
+    fn finds_legacy_sections() {
+        let readme = "<!--- benchmarking table --->\nx\n<!--- benchmarking table --->\n<!--- aoc:status ---><!--- /aoc:status --->";
+        let sections = find_sections(readme).unwrap();
+        assert_eq!(sections[0].name, "benchmarks");
+        assert_eq!(sections[1].name, "status");
+    }
+
+    #[test]
+    #[should_panic]
+    fn errors_if_section_not_closed() {
+        find_sections("<!--- aoc:status --->\n<!--- /aoc:stars --->").unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn errors_if_too_many_legacy_markers() {
+        find_sections(&"<!--- benchmarking table --->".repeat(3)).unwrap();
+    }
+
+    #[test]
+    fn updates_sections() {
+        let mut readme = [
+            "# readme",
+            "<!--- aoc:status --->",
+            "<!--- /aoc:status --->",
+            "<!--- advent_readme_stars table --->",
+            "<!--- advent_readme_stars table --->",
+            "<!--- aoc:foo --->",
+            "keep",
+            "<!--- /aoc:foo --->",
+        ]
+        .join("\n");
+
+        let (updated, unknown) =
+            update_content(&mut readme, &get_mock_context(), None, None).unwrap();
+        assert_eq!(updated, vec!["status", "stars"]);
+        assert_eq!(unknown, vec!["foo"]);
+
+        assert!(readme.contains(
+            "<!--- aoc:status --->\n⭐ **2** stars · **1** days benchmarked in **3.00ms**\n<!--- /aoc:status --->"
+        ));
+        assert!(readme.contains("<!--- aoc:stars --->\n## 2024 Results\n"));
+        assert!(!readme.contains("advent_readme_stars"));
+        assert!(readme.contains("<!--- aoc:foo --->\nkeep\n<!--- /aoc:foo --->"));
+
+        // updating is idempotent.
+        let before = readme.clone();
+        update_content(&mut readme, &get_mock_context(), None, None).unwrap();
+        assert_eq!(readme, before);
+    }
+
+    #[test]
+    fn updates_selected_sections() {
+        let mut readme = "<!--- aoc:status --->\n<!--- /aoc:status --->\n<!--- aoc:stars --->\n<!--- /aoc:stars --->".to_string();
+        let (updated, _) =
+            update_content(&mut readme, &get_mock_context(), Some(&["stars"]), None).unwrap();
+        assert_eq!(updated, vec!["stars"]);
+        assert!(readme.starts_with("<!--- aoc:status --->\n<!--- /aoc:status --->"));
+    }
+
+    #[test]
+    fn updates_sections_of_year() {
+        let mut readme = "<!--- aoc:status --->\n<!--- /aoc:status --->\n<!--- aoc:status:2024 --->\n<!--- /aoc:status:2024 --->\n<!--- aoc:status:2023 --->\n<!--- /aoc:status:2023 --->".to_string();
+        let (updated, _) =
+            update_content(&mut readme, &get_mock_context(), None, Some(2024)).unwrap();
+        assert_eq!(updated, vec!["status:2024"]);
+        assert!(readme.starts_with("<!--- aoc:status --->\n<!--- /aoc:status --->"));
+        assert!(readme.contains("<!--- aoc:status:2024 --->\n⭐ **2** stars"));
+        assert!(readme.ends_with("<!--- aoc:status:2023 --->\n<!--- /aoc:status:2023 --->"));
+        assert_eq!(section_name("status", Some(2024)), "status:2024");
+    }
+
+    #[test]
+    fn skips_sections_without_data() {
+        let mut readme = "<!--- aoc:benchmarks --->\nold\n<!--- /aoc:benchmarks --->\n<!--- benchmarking table --->\nold\n<!--- benchmarking table --->".to_string();
+        let (updated, _) = update_content(&mut readme, &Context::default(), None, None).unwrap();
+        assert!(updated.is_empty());
+        assert_eq!(
+            readme,
+            "<!--- aoc:benchmarks --->\nold\n<!--- /aoc:benchmarks --->\n<!--- aoc:benchmarks --->\nold\n<!--- /aoc:benchmarks --->"
+        );
+    }
+}
@@ -1,183 +1,386 @@
-/// Module that updates the readme me with timing information.
-/// The approach taken is similar to how `aoc-readme-stars` handles this.
-use std::{fs, io};
+/// Generates the `benchmarks` section of the readme from the stored timings.
+use std::{env, time::Duration};
 
+use crate::template::memory::format_bytes;
+use crate::template::paths::paths;
+use crate::template::readme::Context;
 use crate::template::timings::Timings;
 use crate::template::Day;
 
-static MARKER: &str = "<!--- benchmarking table --->";
+const BAR_WIDTH: usize = 10;
+const BAR_PARTS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
 
-#[allow(dead_code)]
-#[derive(Debug)]
-pub enum Error {
-    Parser(String),
-    IO(io::Error),
+/// Order of the days in the table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Sort {
+    #[default]
+    Day,
+    /// Slowest day first.
+    Cost,
 }
 
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Self {
-        Error::IO(e)
-    }
+/// Optional parts of the benchmark table.
+#[derive(Clone, Debug, Default)]
+pub struct TableOptions {
+    /// Show the combined time of all phases.
+    pub total: bool,
+    /// Show the share of the total runtime.
+    pub share: bool,
+    /// Show a bar relative to the slowest day.
+    pub bar: bool,
+    pub sort: Sort,
+    /// Number of slowest days to call out below the table.
+    pub slowest: usize,
+    /// Path of a chart to embed above the table.
+    pub chart: Option<String>,
 }
 
-pub struct TablePosition {
-    pos_start: usize,
-    pos_end: usize,
+impl TableOptions {
+    /// Read the options from `AOC_BENCH_COLUMNS`, `AOC_BENCH_SORT` and `AOC_BENCH_SLOWEST`.
+    pub fn from_env() -> Self {
+        let mut options = Self::default();
+
+        if let Ok(columns) = env::var("AOC_BENCH_COLUMNS") {
+            for column in columns.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+                match column {
+                    "total" => options.total = true,
+                    "share" => options.share = true,
+                    "bar" => options.bar = true,
+                    x => eprintln!(
+                        "Unknown benchmark column `{x}` in AOC_BENCH_COLUMNS, expected `total`, `share` or `bar`."
+                    ),
+                }
+            }
+        }
+
+        match env::var("AOC_BENCH_SORT").as_deref() {
+            Ok("cost") => options.sort = Sort::Cost,
+            Ok("day") | Err(_) => {}
+            Ok(x) => eprintln!("Unknown AOC_BENCH_SORT `{x}`, expected `day` or `cost`."),
+        }
+
+        options.slowest = env::var("AOC_BENCH_SLOWEST")
+            .ok()
+            .and_then(|x| x.parse().ok())
+            .unwrap_or(0);
+
+        options
+    }
 }
 
 #[must_use]
 pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+    format!("./{}", paths().bin_path(day))
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
-
-    if matches.len() > 2 {
-        return Err(Error::Parser(
-            "{}: too many occurences of marker in README.".into(),
-        ));
+/// The benchmark table, or [`None`] if no timings were stored.
+pub fn generate(context: &Context) -> Option<String> {
+    if context.timings.data.is_empty() {
+        return None;
     }
 
-    let pos_start = matches
-        .first()
-        .map(|m| m.0)
-        .ok_or_else(|| Error::Parser("Could not find table start position.".into()))?;
-
-    let pos_end = matches
-        .last()
-        .map(|m| m.0 + m.1.len())
-        .ok_or_else(|| Error::Parser("Could not find table end position.".into()))?;
-
-    Ok(TablePosition { pos_start, pos_end })
+    Some(construct_table(
+        "##",
+        &context.timings,
+        context.timings.total_millis(),
+        &context.table,
+    ))
 }
 
-fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
+fn construct_table(
+    prefix: &str,
+    timings: &Timings,
+    total_millis: f64,
+    options: &TableOptions,
+) -> String {
     let header = format!("{prefix} Benchmarks");
+    // only show the memory column if the heap usage of some day was measured.
+    let has_memory = timings.data.iter().any(|t| t.memory.is_some());
+    let total_nanos = total_millis * 1_000_000_f64;
+    let max_nanos = timings
+        .data
+        .iter()
+        .map(|t| t.total_nanos)
+        .fold(0_f64, f64::max);
 
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
-    ];
-
-    for timing in timings.data {
-        let path = get_path_for_bin(timing.day);
-        lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
-            timing.day.into_inner(),
-            path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
-        ));
+    let mut columns = vec!["Day", "Parse", "Part 1", "Part 2"];
+    if options.total {
+        columns.push("Total");
+    }
+    if options.share {
+        columns.push("Share");
+    }
+    if options.bar {
+        columns.push("Cost");
+    }
+    if has_memory {
+        columns.push("Peak memory");
+    }
+
+    let mut lines: Vec<String> = vec![header, String::new()];
+    if let Some(chart) = &options.chart {
+        lines.push(format!("![Benchmark chart]({chart})"));
+        lines.push(String::new());
+    }
+    lines.push(format!("| {} |", columns.join(" | ")));
+    lines.push(format!("| {}  |", vec![":---:"; columns.len()].join(" | ")));
+
+    let mut data = timings.data.clone();
+    if options.sort == Sort::Cost {
+        data.sort_by(|a, b| b.total_nanos.total_cmp(&a.total_nanos));
+    }
+
+    for timing in &data {
+        let mut cells = vec![
+            format!(
+                "[Day {}]({})",
+                timing.day.into_inner(),
+                get_path_for_bin(timing.day)
+            ),
+            timing
+                .parse
+                .map_or_else(|| "-".into(), |x| format!("`{x}`")),
+            format!(
+                "`{}`",
+                timing.part_1.map_or_else(|| "-".into(), |x| x.to_string())
+            ),
+            format!(
+                "`{}`",
+                timing.part_2.map_or_else(|| "-".into(), |x| x.to_string())
+            ),
+        ];
+        if options.total {
+            cells.push(format!("`{}`", format_nanos(timing.total_nanos)));
+        }
+        if options.share {
+            cells.push(format_share(timing.total_nanos, total_nanos));
+        }
+        if options.bar {
+            cells.push(bar(timing.total_nanos / max_nanos, BAR_WIDTH));
+        }
+        if has_memory {
+            cells.push(
+                timing
+                    .memory
+                    .map_or_else(|| "-".into(), |x| format!("`{}`", format_bytes(x.peak))),
+            );
+        }
+        lines.push(format!("| {} |", cells.join(" | ")));
     }
 
     lines.push(String::new());
     lines.push(format!("**Total: {total_millis:.2}ms**"));
-    lines.push(MARKER.into());
+
+    let mut slowest: Vec<_> = timings
+        .data
+        .iter()
+        .filter(|t| t.total_nanos > 0_f64)
+        .collect();
+    slowest.sort_by(|a, b| b.total_nanos.total_cmp(&a.total_nanos));
+    slowest.truncate(options.slowest);
+
+    if !slowest.is_empty() {
+        let days: Vec<String> = slowest
+            .iter()
+            .map(|t| {
+                format!(
+                    "Day {} (`{}`, {})",
+                    t.day.into_inner(),
+                    format_nanos(t.total_nanos),
+                    format_share(t.total_nanos, total_nanos)
+                )
+            })
+            .collect();
+        lines.push(String::new());
+        lines.push(format!("**Slowest:** {}", days.join(", ")));
+    }
+
+    if let Some(environment) = &timings.environment {
+        lines.push(String::new());
+        lines.push(format!("_Measured on {environment}._"));
+    }
 
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
-    s.replace_range(positions.pos_start..positions.pos_end, &table);
-    Ok(())
+fn format_nanos(nanos: f64) -> String {
+    format!("{:.1?}", Duration::from_nanos(nanos as u64))
+}
+
+fn format_share(nanos: f64, total_nanos: f64) -> String {
+    if total_nanos > 0_f64 {
+        format!("{:.1}%", nanos / total_nanos * 100_f64)
+    } else {
+        "-".into()
+    }
 }
 
-pub fn update(timings: Timings) -> Result<(), Error> {
-    let path = "README.md";
-    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
-    let total_millis = timings.total_millis();
-    update_content(&mut readme, timings, total_millis)?;
-    fs::write(path, &readme)?;
-    Ok(())
+/// Renders `fraction` of `width` characters as a bar of unicode blocks, with eighth-block precision.
+fn bar(fraction: f64, width: usize) -> String {
+    if !fraction.is_finite() {
+        return String::new();
+    }
+
+    let eighths = (fraction.clamp(0_f64, 1_f64) * (width * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if eighths % 8 > 0 {
+        bar.push(BAR_PARTS[eighths % 8 - 1]);
+    }
+    bar
 }
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use std::time::Duration;
+
+    use super::{bar, construct_table, Sort, TableOptions};
+    use crate::{
+        day, template::environment::Environment, template::memory::Memory,
+        template::readme::Context, template::timings::Timing, template::timings::Timings,
+    };
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
                     day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
+                    parse: Some(Duration::from_millis(5).into()),
+                    part_1: Some(Duration::from_millis(10).into()),
+                    part_2: Some(Duration::from_millis(20).into()),
                     total_nanos: 3e+10,
+                    failures: vec![],
+                    memory: None,
                 },
                 Timing {
                     day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
+                    parse: None,
+                    part_1: Some(Duration::from_millis(30).into()),
+                    part_2: Some(Duration::from_millis(40).into()),
                     total_nanos: 7e+10,
+                    failures: vec![],
+                    memory: None,
                 },
                 Timing {
                     day: day!(4),
-                    part_1: Some("40ms".into()),
-                    part_2: Some("50ms".into()),
+                    parse: None,
+                    part_1: Some(Duration::from_millis(40).into()),
+                    part_2: Some(Duration::from_millis(50).into()),
                     total_nanos: 9e+10,
+                    failures: vec![],
+                    memory: None,
                 },
             ],
+            environment: None,
         }
     }
 
     #[test]
-    #[should_panic]
-    fn errors_if_marker_not_present() {
-        let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+    fn format_benchmarks() {
+        let s = construct_table("##", &get_mock_timings(), 190.0, &TableOptions::default());
+        let expected = [
+            "## Benchmarks",
+            "",
+            "| Day | Parse | Part 1 | Part 2 |",
+            "| :---: | :---: | :---: | :---:  |",
+            "| [Day 1](./src/bin/01.rs) | `5.0ms` | `10.0ms` | `20.0ms` |",
+            "| [Day 2](./src/bin/02.rs) | - | `30.0ms` | `40.0ms` |",
+            "| [Day 4](./src/bin/04.rs) | - | `40.0ms` | `50.0ms` |",
+            "",
+            "**Total: 190.00ms**",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
     }
 
     #[test]
-    #[should_panic]
-    fn errors_if_too_many_markers_present() {
-        let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-    }
+    fn format_memory_column() {
+        let mut timings = get_mock_timings();
+        timings.data[0].memory = Some(Memory {
+            allocations: 10,
+            bytes: 4096,
+            peak: 1536,
+        });
 
-    #[test]
-    fn updates_empty_benchmarks() {
-        let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        assert_eq!(s.contains("## Benchmarks"), true);
+        let s = construct_table("##", &timings, 190.0, &TableOptions::default());
+        assert!(s.contains("| Day | Parse | Part 1 | Part 2 | Peak memory |"));
+        assert!(
+            s.contains("| [Day 1](./src/bin/01.rs) | `5.0ms` | `10.0ms` | `20.0ms` | `1.5 KiB` |")
+        );
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | - | `30.0ms` | `40.0ms` | - |"));
     }
 
     #[test]
-    fn updates_existing_benchmarks() {
-        let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
-        assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
+    fn format_environment() {
+        let mut timings = get_mock_timings();
+        timings.environment = Some(Environment {
+            cpu: Some("Ryzen".into()),
+            cores: Some(16),
+            rustc: None,
+            target: None,
+            profile: "release".into(),
+            rayon_threads: None,
+            git_commit: None,
+        });
+
+        let s = construct_table("##", &timings, 190.0, &TableOptions::default());
+        assert!(s.contains(
+            "**Total: 190.00ms**\n\n_Measured on Ryzen (16 cores), unknown, unknown, release profile._"
+        ));
     }
 
     #[test]
-    fn format_benchmarks() {
-        let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+    fn format_optional_columns() {
+        let options = TableOptions {
+            total: true,
+            share: true,
+            bar: true,
+            sort: Sort::Cost,
+            slowest: 2,
+            chart: None,
+        };
+
+        let mut timings = get_mock_timings();
+        for (timing, total_nanos) in timings.data.iter_mut().zip([3e+7, 7e+7, 9e+7]) {
+            timing.total_nanos = total_nanos;
+        }
+
+        let s = construct_table("##", &timings, 190.0, &options);
         let expected = [
-            "foo",
-            "bar",
-            "<!--- benchmarking table --->",
             "## Benchmarks",
             "",
-            "| Day | Part 1 | Part 2 |",
-            "| :---: | :---: | :---:  |",
-            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
-            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
-            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "| Day | Parse | Part 1 | Part 2 | Total | Share | Cost |",
+            "| :---: | :---: | :---: | :---: | :---: | :---: | :---:  |",
+            "| [Day 4](./src/bin/04.rs) | - | `40.0ms` | `50.0ms` | `90.0ms` | 47.4% | ██████████ |",
+            "| [Day 2](./src/bin/02.rs) | - | `30.0ms` | `40.0ms` | `70.0ms` | 36.8% | ███████▊ |",
+            "| [Day 1](./src/bin/01.rs) | `5.0ms` | `10.0ms` | `20.0ms` | `30.0ms` | 15.8% | ███▍ |",
             "",
             "**Total: 190.00ms**",
-            "<!--- benchmarking table --->",
-            "baz",
+            "",
+            "**Slowest:** Day 4 (`90.0ms`, 47.4%), Day 2 (`70.0ms`, 36.8%)",
         ]
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn renders_bars() {
+        assert_eq!(bar(1.0, 4), "████");
+        assert_eq!(bar(0.5, 4), "██");
+        assert_eq!(bar(0.0625, 4), "▎");
+        assert_eq!(bar(0.0, 4), "");
+        assert_eq!(bar(f64::NAN, 4), "");
+    }
+
+    #[test]
+    fn format_chart() {
+        let options = TableOptions {
+            chart: Some("./.assets/benchmarks.svg".into()),
+            ..TableOptions::default()
+        };
+
+        let s = construct_table("##", &get_mock_timings(), 190.0, &options);
+        assert!(s.contains(
+            "## Benchmarks\n\n![Benchmark chart](./.assets/benchmarks.svg)\n\n| Day | Parse | Part 1 | Part 2 |"
+        ));
+    }
 }
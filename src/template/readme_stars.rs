@@ -0,0 +1,161 @@
+/// Generates the `stars` section of the readme from the solved parts of each day.
+/// Replaces the table that was maintained by the `advent-readme-stars` Github action.
+use std::fs;
+
+use crate::template::paths::{paths, selected_year};
+use crate::template::readme::{self, Context};
+use crate::template::stars::Stars;
+use crate::template::Day;
+
+static STAR: &str = "🌟";
+
+/// The stars table, or [`None`] if no part was solved.
+pub fn generate(context: &Context) -> Option<String> {
+    if context.stars.data.is_empty() {
+        return None;
+    }
+
+    Some(construct_table(&context.stars, context.year))
+}
+
+fn construct_table(stars: &Stars, year: Option<u16>) -> String {
+    let header = match year {
+        Some(year) => format!("## {year} Results"),
+        None => "## Results".into(),
+    };
+
+    let mut lines: Vec<String> = vec![header, String::new()];
+    lines.push("| Day | Part 1 | Part 2 |".into());
+    lines.push("| :---: | :---: | :---: |".into());
+
+    for day_stars in stars.data.iter().filter(|s| s.part_1 || s.part_2) {
+        let day = day_stars.day.into_inner();
+        let link = match year {
+            Some(year) => format!("[Day {day}](https://adventofcode.com/{year}/day/{day})"),
+            None => format!("Day {day}"),
+        };
+        let star = |solved: bool| if solved { STAR } else { " " };
+
+        lines.push(format!(
+            "| {link} | {} | {} |",
+            star(day_stars.part_1),
+            star(day_stars.part_2)
+        ));
+    }
+
+    lines.join("\n")
+}
+
+/// Parse the stars of an existing table, so that tables created by the Github action are kept.
+fn parse_table(s: &str) -> Stars {
+    let mut stars = Stars::default();
+
+    for line in s.lines() {
+        let cells: Vec<&str> = line.split('|').map(str::trim).collect();
+        let [_, day, part_1, part_2, _] = cells[..] else {
+            continue;
+        };
+
+        let Some(day) = day
+            .strip_prefix("[Day ")
+            .or_else(|| day.strip_prefix("Day "))
+            .and_then(|x| x.split(|c: char| !c.is_ascii_digit()).next())
+            .and_then(|x| x.parse().ok())
+            .and_then(Day::new)
+        else {
+            continue;
+        };
+
+        for (part, cell) in [(1, part_1), (2, part_2)] {
+            if !cell.is_empty() {
+                stars.add(day, part);
+            }
+        }
+    }
+
+    stars
+}
+
+/// The stored stars. Until stars are stored, the stars of the table in the readme are used.
+/// Fails if the stored stars can not be read.
+pub fn load(readme: &str) -> Result<Stars, String> {
+    if Stars::is_stored() {
+        return Stars::read_from_file();
+    }
+
+    Ok(
+        readme::section_content(readme, &readme::section_name("stars", selected_year()))
+            .map(parse_table)
+            .unwrap_or_default(),
+    )
+}
+
+/// Like [`load`], reading the readme from disk.
+pub fn read() -> Result<Stars, String> {
+    let readme = fs::read(&paths().readme)
+        .map(|x| String::from_utf8_lossy(&x).to_string())
+        .unwrap_or_default();
+    load(&readme)
+}
+
+/// Update the stars in the readme if solutions earned stars since `previous` was read.
+/// Solutions only store their stars, so the readme is written once by the command that ran them.
+pub fn update_readme(previous: &Stars) {
+    // stars that can not be read were reported by the solutions, which did not store any.
+    let Ok(stars) = read() else {
+        return;
+    };
+
+    if stars.count() == previous.count() {
+        return;
+    }
+
+    if readme::update(Some(&["stars", "status"])).is_err() {
+        eprintln!("Failed to update the stars in the readme.");
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{construct_table, parse_table};
+    use crate::{day, template::stars::Stars};
+
+    fn get_mock_stars() -> Stars {
+        let mut stars = Stars::default();
+        stars.add(day!(1), 1);
+        stars.add(day!(1), 2);
+        stars.add(day!(3), 1);
+        stars
+    }
+
+    #[test]
+    fn format_stars() {
+        let s = construct_table(&get_mock_stars(), Some(2024));
+        let expected = [
+            "## 2024 Results",
+            "",
+            "| Day | Part 1 | Part 2 |",
+            "| :---: | :---: | :---: |",
+            "| [Day 1](https://adventofcode.com/2024/day/1) | 🌟 | 🌟 |",
+            "| [Day 3](https://adventofcode.com/2024/day/3) | 🌟 |   |",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+
+    #[test]
+    fn format_stars_without_year() {
+        let s = construct_table(&get_mock_stars(), None);
+        assert!(s.starts_with("## Results\n"));
+        assert!(s.contains("| Day 1 | 🌟 | 🌟 |"));
+    }
+
+    #[test]
+    fn parses_existing_tables() {
+        let s = construct_table(&get_mock_stars(), Some(2024));
+        assert_eq!(parse_table(&s).data, get_mock_stars().data);
+
+        let s = construct_table(&get_mock_stars(), None);
+        assert_eq!(parse_table(&s).data, get_mock_stars().data);
+    }
+}
@@ -0,0 +1,76 @@
+/// Runs several solutions in a single process.
+///
+/// The build script generates a table of every solution in `src/bin`, which is compiled into the
+/// `registry` binary. `run_multi` invokes this binary once instead of spawning cargo for every day.
+use std::{
+    env, fs,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::template::paths::{paths, select_year, selected_year};
+use crate::template::protocol::{Message, Record};
+use crate::template::{Day, ANSI_BOLD, ANSI_RESET};
+
+/// A solution that can be called in-process.
+pub struct Solution {
+    /// The year of solutions named like `2024_05.rs`.
+    pub year: Option<u16>,
+    pub day: Day,
+    /// Runs all parts of the solution against an input.
+    pub run: fn(&str),
+}
+
+/// Run the solutions for the days passed via `--days 01,02,...`, or all registered days if not passed.
+/// Only solutions of the year passed via `--year` run, or solutions without a year if not passed.
+/// Every day is announced with a start event, so the records can be attributed to their day.
+/// Days without a registered solution are announced, but produce no records.
+pub fn run(solutions: &[Solution]) {
+    if let Some(year) = arg_value("--year").and_then(|x| x.parse().ok()) {
+        select_year(year);
+    }
+
+    let solutions: Vec<&Solution> = solutions
+        .iter()
+        .filter(|s| s.year == selected_year())
+        .collect();
+
+    let days = requested_days().unwrap_or_else(|| solutions.iter().map(|s| s.day).collect());
+
+    for day in days {
+        if Record::is_enabled() {
+            println!("{}", Message::Start(day).to_line());
+        } else {
+            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+        }
+
+        let Some(solution) = solutions.iter().find(|s| s.day == day) else {
+            continue;
+        };
+
+        let path = env::current_dir().unwrap().join(paths().input(day));
+
+        // NOTE: print to stdout, so the message shows up in order with the output of the other days.
+        let Ok(input) = fs::read_to_string(path) else {
+            println!("could not open input file");
+            continue;
+        };
+
+        // keep going with the next day if a solution panics, the panic message is printed to stderr.
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| (solution.run)(&input)));
+    }
+}
+
+fn arg_value(name: &str) -> Option<String> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == name)?;
+    args.get(index + 1).cloned()
+}
+
+fn requested_days() -> Option<Vec<Day>> {
+    Some(
+        arg_value("--days")?
+            .split(',')
+            .filter_map(|x| x.parse().ok())
+            .collect(),
+    )
+}
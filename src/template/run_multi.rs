@@ -1,48 +1,193 @@
-use std::{collections::HashSet, io};
+use std::{collections::HashSet, io, time::Duration};
 
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::template::answers::Check;
+use crate::template::environment::Environment;
+use crate::template::paths::paths;
+use crate::template::protocol::{Message, Record};
+use crate::template::runner::print_record;
+use crate::template::{BenchConfig, Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
 use super::{
     all_days,
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
-    let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+/// Options for running a set of days.
+#[derive(Clone, Debug, Default)]
+pub struct RunOptions {
+    pub is_release: bool,
+    /// Solutions are benched if a budget is passed.
+    pub bench: Option<BenchConfig>,
+    /// Run every day in its own binary instead of the solution registry.
+    pub is_isolated: bool,
+    /// Store the answers as the known answers.
+    pub is_record: bool,
+    /// Wall-clock limit for the first execution of a part.
+    pub timeout: Option<Duration>,
+    /// Build the solutions with the `count-alloc` feature to report their heap usage.
+    pub is_count_alloc: bool,
+}
 
-    let mut need_space = false;
+/// The outcome of running a set of days.
+pub struct RunSummary {
+    /// Timings of the days, if the solutions were benched.
+    pub timings: Option<Timings>,
+    /// Number of parts whose answer disagrees with the known answer.
+    pub incorrect: usize,
+    /// Number of parts that panicked or timed out.
+    pub failed: usize,
+}
 
+/// Run the solutions for a set of days.
+///
+/// By default, all days run in a single process via the solution registry. Days the registry
+/// could not run, e.g. because a solution does not compile, fall back to running their own binary.
+pub fn run_multi(days_to_run: &HashSet<Day>, options: &RunOptions) -> RunSummary {
     // NOTE: use non-duplicate, sorted day values.
-    all_days()
-        .filter(|day| days_to_run.contains(day))
-        .for_each(|day| {
-            if need_space {
-                println!();
-            }
-            need_space = true;
+    let days: Vec<Day> = all_days().filter(|day| days_to_run.contains(day)).collect();
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
+    let mut printer = Printer::default();
+    let mut records: Vec<Record> = vec![];
+    let mut started: HashSet<Day> = HashSet::new();
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+    if !options.is_isolated && !days.is_empty() {
+        child_commands::run_registry(&days, options, |message| match message {
+            Message::Start(day) => {
+                printer.start_day(day);
+                started.insert(day);
+            }
+            Message::Record(record) => {
+                printer.record(&record);
+                records.push(*record);
+            }
+        })
+        .unwrap();
 
-            if output.is_empty() {
-                println!("Not solved.");
-            } else {
-                let val = child_commands::parse_exec_time(&output, day);
-                timings.push(val);
+        // a timeout ends the registry, the parts after it run in their own process.
+        if let Some(record) = records.last() {
+            if let Some(part) = child_commands::resume_part(&records) {
+                run_isolated(record.day, Some(part), options, &mut printer, &mut records);
             }
-        });
+        }
+
+        if started.len() < days.len() {
+            printer.end_day();
+            eprintln!(
+                "Could not run all days in one process, running the remaining days one by one."
+            );
+        }
+    }
+
+    for day in days.iter().filter(|day| !started.contains(day)) {
+        printer.start_day(*day);
+        run_isolated(*day, None, options, &mut printer, &mut records);
+    }
 
-    if is_timed {
-        let timings = Timings { data: timings };
+    printer.end_day();
+
+    let incorrect = records
+        .iter()
+        .filter(|r| matches!(r.check, Check::Incorrect(_)))
+        .count();
+
+    let failed = records
+        .iter()
+        .filter(|r| r.status.failure().is_some())
+        .count();
+
+    if failed > 0 {
+        println!("\n{ANSI_BOLD}✖ {failed} part(s) panicked or timed out.{ANSI_RESET}");
+    }
+
+    if incorrect > 0 {
+        println!("\n{ANSI_BOLD}✘ {incorrect} answer(s) differ from the known answers.{ANSI_RESET}");
+    }
+
+    let timings = options.bench.map(|_| {
+        let timings = Timings {
+            data: days
+                .iter()
+                .filter(|day| records.iter().any(|r| r.day == **day))
+                .map(|day| child_commands::timing_from_records(&records, *day))
+                .collect(),
+            environment: Some(Environment::detect(options.is_release)),
+        };
         let total_millis = timings.total_millis();
         println!(
             "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
         );
-        Some(timings)
-    } else {
-        None
+        timings
+    });
+
+    RunSummary {
+        timings,
+        incorrect,
+        failed,
+    }
+}
+
+/// Run a day in its own binary, starting at `from_part` if passed.
+/// A part that timed out ends the process, so the parts after it run in another one.
+fn run_isolated(
+    day: Day,
+    from_part: Option<u8>,
+    options: &RunOptions,
+    printer: &mut Printer,
+    records: &mut Vec<Record>,
+) {
+    let mut from_part = from_part;
+
+    loop {
+        let count = records.len();
+        child_commands::run_solution(day, from_part, options, |message| {
+            if let Message::Record(record) = message {
+                printer.record(&record);
+                records.push(*record);
+            }
+        })
+        .unwrap();
+
+        match child_commands::resume_part(&records[count..]) {
+            Some(part) => from_part = Some(part),
+            None => break,
+        }
+    }
+}
+
+/// Prints the output of one day at a time.
+#[derive(Default)]
+struct Printer {
+    need_space: bool,
+    is_open: bool,
+    is_solved: bool,
+}
+
+impl Printer {
+    fn start_day(&mut self, day: Day) {
+        self.end_day();
+
+        if self.need_space {
+            println!();
+        }
+        self.need_space = true;
+
+        println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+        println!("------");
+
+        self.is_open = true;
+        self.is_solved = false;
+    }
+
+    fn record(&mut self, record: &Record) {
+        print_record(record);
+        self.is_solved = true;
+    }
+
+    fn end_day(&mut self) {
+        if self.is_open && !self.is_solved {
+            println!("Not solved.");
+        }
+        self.is_open = false;
     }
 }
 
@@ -61,13 +206,16 @@ impl From<std::io::Error> for Error {
 
 #[must_use]
 pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+    format!("./{}", paths().bin_path(day))
 }
 
-/// All solutions live in isolated binaries.
-/// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
+/// All solutions live in isolated binaries, and are additionally compiled into the registry bin.
+/// This module encapsulates interaction with these binaries, both invoking them as well as consuming the records they emit.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
+    use super::{get_path_for_bin, Error, RunOptions};
+    use crate::template::paths::{paths, selected_year};
+    use crate::template::protocol::{Message, Phase, Record, Status};
+    use crate::template::timings::{Failure, PhaseTiming};
     use crate::template::Day;
     use std::{
         io::{BufRead, BufReader},
@@ -76,31 +224,100 @@ pub mod child_commands {
         thread,
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Run the solution bin for a given day, skipping the parts before `from_part` if passed.
+    pub fn run_solution(
+        day: Day,
+        from_part: Option<u8>,
+        options: &RunOptions,
+        on_message: impl FnMut(Message),
+    ) -> Result<(), Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+            return Ok(());
+        }
+
+        let mut args = build_args(&paths().bin(day), options);
+        if let Some(part) = from_part {
+            args.push("--from-part".into());
+            args.push(part.to_string());
+        }
+        run_child(&args, on_message)
+    }
+
+    /// The part to continue a day with if the process running it ended after the first part timed out.
+    /// The process exits on a timeout, so the timed out part is the last record it emitted.
+    pub fn resume_part(records: &[Record]) -> Option<u8> {
+        match records.last() {
+            Some(record) if record.status == Status::TimedOut && record.phase == Phase::Part(1) => {
+                Some(2)
+            }
+            _ => None,
+        }
+    }
+
+    /// Run the solutions for a set of days in a single process with the registry bin.
+    pub fn run_registry(
+        days: &[Day],
+        options: &RunOptions,
+        on_message: impl FnMut(Message),
+    ) -> Result<(), Error> {
+        let mut args = build_args("registry", options);
+        args.push("--days".into());
+        args.push(
+            days.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        if let Some(year) = selected_year() {
+            args.push("--year".into());
+            args.push(year.to_string());
+        }
+        run_child(&args, on_message)
+    }
+
+    fn build_args(bin: &str, options: &RunOptions) -> Vec<String> {
+        let mut args: Vec<String> =
+            vec!["run".into(), "--quiet".into(), "--bin".into(), bin.into()];
+
+        if options.is_release {
+            args.push("--release".into());
+        }
+
+        if options.is_count_alloc {
+            args.push("--features".into());
+            args.push("count-alloc".into());
         }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+        // ask the child to emit machine-readable records instead of human-readable output.
+        args.push("--".into());
+        args.push("--format".into());
+        args.push("json".into());
 
-        if is_release {
-            args.push("--release");
+        if let Some(bench) = options.bench {
+            // mirror `--time` flag and the bench budget to child invocations.
+            args.push("--time".into());
+            args.extend(bench.to_args());
         }
 
-        if is_timed {
-            // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
+        if options.is_record {
+            args.push("--record".into());
         }
 
+        if let Some(timeout) = options.timeout {
+            args.push("--timeout".into());
+            args.push(format!("{}ns", timeout.as_nanos()));
+        }
+
+        args
+    }
+
+    fn run_child(args: &[String], mut on_message: impl FnMut(Message)) -> Result<(), Error> {
         // spawn child command with piped stdout/stderr.
-        // forward output to stdout/stderr while grabbing stdout lines.
+        // forward stderr and any output of the solutions themselves while consuming the messages.
 
         let mut cmd = Command::new("cargo")
-            .args(&args)
+            .args(args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()?;
@@ -108,8 +325,6 @@ pub mod child_commands {
         let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
         let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
 
-        let mut output = vec![];
-
         let thread = thread::spawn(move || {
             stderr.lines().for_each(|line| {
                 eprintln!("{}", line.unwrap());
@@ -118,75 +333,62 @@ pub mod child_commands {
 
         for line in stdout.lines() {
             let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
+            match Message::from_line(&line) {
+                Ok(message) => on_message(message),
+                Err(_) => println!("{line}"),
+            }
         }
 
         thread.join().unwrap();
         cmd.wait()?;
 
-        Ok(output)
+        Ok(())
     }
 
-    pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
+    /// Collect the timings of the phases that ran to completion, and the reasons of parts that failed.
+    pub fn timing_from_records(records: &[Record], day: Day) -> super::Timing {
         let mut timings = super::Timing {
             day,
+            parse: None,
             part_1: None,
             part_2: None,
             total_nanos: 0_f64,
+            failures: vec![],
+            memory: None,
         };
 
-        output
-            .iter()
-            .filter_map(|l| {
-                if !l.contains(" samples)") {
-                    return None;
-                }
-
-                let Some((timing_str, nanos)) = parse_time(l) else {
-                    eprintln!("Could not parse timings from line: {l}");
-                    return None;
-                };
-
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
-            })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
-                }
-
-                timings.total_nanos += nanos;
+        records.iter().filter(|r| r.day == day).for_each(|record| {
+            if let (Phase::Part(part), Some(reason)) = (record.phase, record.status.failure()) {
+                timings.failures.push(Failure { part, reason });
+            }
+
+            if record.status != Status::Ok {
+                return;
+            }
+
+            if let Some(memory) = &record.memory {
+                timings.memory = Some(match &timings.memory {
+                    Some(total) => total.combine(memory),
+                    None => *memory,
+                });
+            }
+
+            let timing = Some(PhaseTiming {
+                nanos: record.stats.mean.as_nanos() as u64,
+                samples: Some(record.stats.samples),
             });
 
-        timings
-    }
+            match record.phase {
+                Phase::Parse => timings.parse = timing,
+                Phase::Part(1) => timings.part_1 = timing,
+                Phase::Part(2) => timings.part_2 = timing,
+                Phase::Part(_) => return,
+            }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
-    }
+            timings.total_nanos += record.stats.mean.as_nanos() as f64;
+        });
 
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
-            .split(" samples)")
-            .next()?
-            .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
-
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
+        timings
     }
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
@@ -205,47 +407,83 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use std::time::Duration;
+
+        use super::{resume_part, timing_from_records};
 
         use crate::day;
+        use crate::template::answers::Check;
+        use crate::template::environment::Environment;
+        use crate::template::memory::Memory;
+        use crate::template::protocol::{Phase, Record, Status};
+        use crate::template::stats::Stats;
+
+        fn record(phase: Phase, answer: Option<&str>, nanos: u64) -> Record {
+            Record {
+                day: day!(1),
+                phase,
+                status: if answer.is_some() || phase == Phase::Parse {
+                    Status::Ok
+                } else {
+                    Status::Unsolved
+                },
+                answer: answer.map(Into::into),
+                check: Check::Unknown,
+                stats: Stats::single(Duration::from_nanos(nanos)),
+                memory: None,
+            }
+        }
 
         #[test]
-        fn parses_execution_times() {
-            let res = parse_exec_time(
+        fn collects_execution_times() {
+            let res = timing_from_records(
                 &[
-                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
-                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
-                    "".into(),
+                    record(Phase::Part(1), Some("0"), 74),
+                    record(Phase::Part(2), Some("10"), 74_130_000),
                 ],
                 day!(1),
             );
-            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+            assert_approx_eq!(res.total_nanos, 74130074_f64);
+            assert_eq!(res.part_1.unwrap().nanos, 74);
+            assert_eq!(res.part_1.unwrap().samples, Some(1));
+            assert_eq!(res.part_1.unwrap().to_string(), "74.0ns");
+            assert_eq!(res.part_2.unwrap().to_string(), "74.1ms");
         }
 
         #[test]
-        fn parses_with_patterns_in_input() {
-            let res = parse_exec_time(
+        fn collects_parse_times() {
+            let res = timing_from_records(
                 &[
-                    "Part 1: @ @ @ ( ) ms (2s @ 5 samples)".into(),
-                    "Part 2: 10s (100ms @ 1 samples)".into(),
-                    "".into(),
+                    record(Phase::Parse, None, 1_500_000),
+                    record(Phase::Part(1), Some("0"), 74),
+                    record(Phase::Part(2), Some("10"), 74_130_000),
                 ],
                 day!(1),
             );
-            assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+            assert_approx_eq!(res.total_nanos, 75630074_f64);
+            assert_eq!(res.parse.unwrap().nanos, 1_500_000);
+        }
+
+        #[test]
+        fn collects_answers_with_patterns() {
+            let res = timing_from_records(
+                &[record(
+                    Phase::Part(1),
+                    Some("@ @ @ ( ) ms (2s @ 5 samples)"),
+                    2_000_000_000,
+                )],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 2000000000_f64);
+            assert_eq!(res.part_1.unwrap().to_string(), "2.0s");
         }
 
         #[test]
-        fn parses_missing_parts() {
-            let res = parse_exec_time(
+        fn collects_missing_parts() {
+            let res = timing_from_records(
                 &[
-                    "Part 1: ✖        ".into(),
-                    "Part 2: ✖        ".into(),
-                    "".into(),
+                    record(Phase::Part(1), None, 100),
+                    record(Phase::Part(2), None, 100),
                 ],
                 day!(1),
             );
@@ -253,5 +491,62 @@ pub mod child_commands {
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn collects_memory() {
+            let mut parse = record(Phase::Parse, None, 100);
+            parse.memory = Some(Memory {
+                allocations: 2,
+                bytes: 100,
+                peak: 100,
+            });
+            let mut part_1 = record(Phase::Part(1), Some("0"), 100);
+            part_1.memory = Some(Memory {
+                allocations: 1,
+                bytes: 50,
+                peak: 20,
+            });
+
+            let res = timing_from_records(&[parse, part_1], day!(1));
+            let memory = res.memory.unwrap();
+            assert_eq!(memory.allocations, 3);
+            assert_eq!(memory.bytes, 150);
+            assert_eq!(memory.peak, 100);
+        }
+
+        #[test]
+        fn collects_failures() {
+            let mut panicked = record(Phase::Part(1), None, 100);
+            panicked.status = Status::Panicked("boom".into());
+            let mut timed_out = record(Phase::Part(2), None, 100);
+            timed_out.status = Status::TimedOut;
+
+            let res = timing_from_records(&[panicked, timed_out], day!(1));
+            assert_approx_eq!(res.total_nanos, 0_f64);
+            assert_eq!(res.part_1.is_none(), true);
+            assert_eq!(res.failures.len(), 2);
+            assert_eq!(res.failures[0].part, 1);
+            assert_eq!(res.failures[0].reason, "panicked: boom");
+            assert_eq!(res.failures[1].reason, "timed out");
+        }
+
+        #[test]
+        fn resumes_after_timeouts() {
+            let mut timed_out = record(Phase::Part(1), None, 100);
+            timed_out.status = Status::TimedOut;
+            assert_eq!(
+                resume_part(&[record(Phase::Parse, None, 100), timed_out.clone()]),
+                Some(2)
+            );
+
+            timed_out.phase = Phase::Part(2);
+            assert_eq!(resume_part(&[timed_out.clone()]), None);
+
+            timed_out.phase = Phase::Parse;
+            assert_eq!(resume_part(&[timed_out]), None);
+
+            assert_eq!(resume_part(&[record(Phase::Part(1), Some("0"), 100)]), None);
+            assert_eq!(resume_part(&[]), None);
+        }
     }
 }
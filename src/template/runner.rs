@@ -1,65 +1,297 @@
 /// Encapsulates code that interacts with solution functions.
+use std::any::Any;
 use std::fmt::Display;
 use std::hint::black_box;
-use std::io::{stdout, Write};
-use std::process::Output;
+use std::io::{stdin, stdout, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{cmp, env, process, thread};
 
-use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::answers::{Answers, Check};
+use crate::template::memory::{self, Memory};
+use crate::template::protocol::{Phase, Record, Status};
+use crate::template::readme_stars;
+use crate::template::stats::Stats;
+use crate::template::submissions::{self, Submission, Verdict};
+use crate::template::{aoc_client, parse_duration, BenchConfig, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{ANSI_BOLD, ANSI_GREEN, ANSI_RED};
 
+/// Run a part of a solution and report its result.
+///
+/// A panic is reported as a failure and does not prevent the next part from running.
+/// A part exceeding the `--timeout` limit is reported as a failure and ends the process, see [`Watchdog`].
+/// Parts before the `--from-part` argument are skipped.
 pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
-    let part_str = format!("Part {part}");
+    if from_part_from_args().is_some_and(|x| part < x) {
+        return;
+    }
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let phase = Phase::Part(part);
+    let is_human = !Record::is_enabled();
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    let timer = Instant::now();
+    let watchdog = Watchdog::start(day, phase);
+    let (status, result, stats, memory) = match catch_panic(|| {
+        run_timed(func, input, |result| {
+            watchdog.disarm();
+            if is_human {
+                print_result(result, &phase.to_string(), "");
+            }
+        })
+    }) {
+        Ok((Some(result), stats, memory)) => (Status::Ok, Some(result), stats, memory),
+        Ok((None, stats, memory)) => (Status::Unsolved, None, stats, memory),
+        Err(status) => (status, None, Stats::single(timer.elapsed()), None),
+    };
+    drop(watchdog);
+
+    let answer = result.as_ref().map(ToString::to_string);
+    let check = check_answer(day, part, answer.as_deref());
+
+    if check == Check::Correct {
+        add_star(day, part);
+    }
+
+    report(&Record {
+        day,
+        phase,
+        status,
+        check,
+        answer,
+        stats,
+        memory,
+    });
 
     if let Some(result) = result {
         submit_result(result, day, part);
     }
 }
 
+/// Run the parse function of a solution once, timing it separately from the parts.
+/// Returns [`None`] if it panicked, in which case the parts can not run.
+///
+/// With `--from-part`, the parse function was already reported by the process that ran the earlier
+/// parts, so it only runs once to provide the input.
+pub fn run_parse<I: Clone, P>(func: impl Fn(I) -> P, input: I, day: Day) -> Option<P> {
+    if from_part_from_args().is_some() {
+        return catch_panic(|| func(input)).ok();
+    }
+
+    let is_human = !Record::is_enabled();
+
+    let timer = Instant::now();
+    let watchdog = Watchdog::start(day, Phase::Parse);
+    let (status, parsed, stats, memory) = match catch_panic(|| {
+        run_timed(func, input, |_| {
+            watchdog.disarm();
+            if is_human {
+                print!("{}:", Phase::Parse);
+            }
+        })
+    }) {
+        Ok((parsed, stats, memory)) => (Status::Ok, Some(parsed), stats, memory),
+        Err(status) => (status, None, Stats::single(timer.elapsed()), None),
+    };
+    drop(watchdog);
+
+    report(&Record {
+        day,
+        phase: Phase::Parse,
+        status,
+        answer: None,
+        check: Check::Unknown,
+        stats,
+        memory,
+    });
+
+    parsed
+}
+
+/// Run `work`, reporting a panic as a failure. The panic message is printed to stderr as usual.
+fn catch_panic<R>(work: impl FnOnce() -> R) -> Result<R, Status> {
+    panic::catch_unwind(AssertUnwindSafe(work))
+        .map_err(|payload| Status::Panicked(panic_message(payload.as_ref())))
+}
+
+/// Ends the process if the first execution of a phase exceeds the `--timeout` limit.
+///
+/// A phase can not be stopped from the outside. Left running, it would keep a core busy and add to
+/// the allocation counters while the next days are benched, so the timeout is reported and the
+/// process exits instead. `run_multi` runs the parts after it and the remaining days in new processes.
+/// Benching after the first execution is not limited.
+struct Watchdog(Option<mpsc::Sender<()>>);
+
+impl Watchdog {
+    fn start(day: Day, phase: Phase) -> Self {
+        let Some(timeout) = timeout_from_args() else {
+            return Self(None);
+        };
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            // a message disarms the watchdog, and so does dropping it.
+            if rx.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+                let check = match phase {
+                    Phase::Part(part) => check_answer(day, part, None),
+                    Phase::Parse => Check::Unknown,
+                };
+                report(&Record {
+                    day,
+                    phase,
+                    status: Status::TimedOut,
+                    check,
+                    answer: None,
+                    stats: Stats::single(timeout),
+                    memory: None,
+                });
+                let _ = stdout().flush();
+                process::exit(1);
+            }
+        });
+
+        Self(Some(tx))
+    }
+
+    fn disarm(&self) {
+        if let Some(tx) = &self.0 {
+            let _ = tx.send(());
+        }
+    }
+}
+
+/// Reads the `--timeout` argument a solution binary was invoked with.
+fn timeout_from_args() -> Option<Duration> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == "--timeout")?;
+    args.get(index + 1).and_then(|x| parse_duration(x).ok())
+}
+
+/// Reads the `--from-part` argument a solution binary was invoked with.
+fn from_part_from_args() -> Option<u8> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == "--from-part")?;
+    args.get(index + 1).and_then(|x| x.parse().ok())
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        (*msg).into()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown".into()
+    }
+}
+
+/// Verify an answer against the known answers in `data/answers.json`.
+/// When invoked with `--record`, the answer is stored as the known answer afterwards,
+/// so the result still reflects the answer that was known before.
+/// If the known answers can not be read, nothing is verified, and recording aborts instead of overwriting them.
+fn check_answer(day: Day, part: u8, answer: Option<&str>) -> Check {
+    let record = env::args().any(|x| x == "--record");
+
+    let mut answers = match Answers::read_from_file() {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("{e}");
+            if record {
+                process::exit(1);
+            }
+            return Check::Unknown;
+        }
+    };
+    let check = answers.check(day, part, answer);
+
+    if let Some(answer) = answer {
+        if record {
+            answers.set(day, part, answer);
+            if let Err(e) = answers.store_file() {
+                eprintln!("Failed to store answer: {e}");
+            }
+        }
+    }
+
+    check
+}
+
+/// Record the star of a part whose answer was verified.
+/// The readme is updated by the command that ran the solution, see [`readme_stars::update_readme`].
+/// Nothing is stored if the stored stars can not be read, so that they are not overwritten.
+fn add_star(day: Day, part: u8) {
+    let mut stars = match readme_stars::read() {
+        Ok(stars) => stars,
+        Err(e) => {
+            eprintln!("{e}");
+            return;
+        }
+    };
+
+    if stars.add(day, part) {
+        if let Err(e) = stars.store_file() {
+            eprintln!("Failed to store stars: {e}");
+        }
+    }
+}
+
+/// Print a record, either as JSON when the runner was invoked with `--format json` or for humans.
+fn report(record: &Record) {
+    if Record::is_enabled() {
+        println!("{}", record.to_line());
+    } else {
+        print_record(record);
+    }
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///
+/// The bench budget can be changed with the `--bench-time`, `--min-samples` and `--max-samples` arguments.
+///
+/// The heap usage is measured for the first execution, if allocations are counted.
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> (T, Stats, Option<Memory>) {
     let timer = Instant::now();
-    let result = {
+    let (result, memory) = memory::measure(|| {
         let input = input.clone();
 
         #[cfg(feature = "dhat-heap")]
         let _profiler = dhat::Profiler::new_heap();
 
         func(input)
-    };
+    });
     let base_time = timer.elapsed();
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
+    let stats = if std::env::args().any(|x| x == "--time") {
         bench(func, input, &base_time)
     } else {
-        (base_time, 1)
+        Stats::single(base_time)
     };
 
-    (result, run.0, run.1)
+    (result, stats, memory)
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+/// Bench a solution part. A tenth of the iterations is spent warming up and is not counted.
+fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> Stats {
     let mut stdout = stdout();
 
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+    if !Record::is_enabled() {
+        print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
+        let _ = stdout.flush();
+    }
+
+    let bench_iterations = BenchConfig::from_args().iterations(base_time);
+    let warmup_iterations = cmp::max(bench_iterations / 10, 1);
 
-    let bench_iterations =
-        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
+    for _ in 0..warmup_iterations {
+        black_box(func(black_box(input.clone())));
+    }
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -71,19 +303,41 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         timers.push(timer.elapsed());
     }
 
-    (
-        #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+    Stats::from_samples(&timers).unwrap_or_else(|| Stats::single(*base_time))
 }
 
-fn average_duration(numbers: &[Duration]) -> u128 {
-    numbers
-        .iter()
-        .map(std::time::Duration::as_nanos)
-        .sum::<u128>()
-        / numbers.len() as u128
+/// Print the human-readable result line and bench stats of a record.
+pub fn print_record(record: &Record) {
+    let check_str = match &record.check {
+        Check::Correct => " ✔".into(),
+        Check::Incorrect(expected) if expected.contains('\n') => " ✘".into(),
+        Check::Incorrect(expected) => format!(" ✘ expected {ANSI_BOLD}{expected}{ANSI_RESET}"),
+        Check::Unknown => String::new(),
+    };
+    let duration_str = format!(
+        "{check_str}{}",
+        format_duration(&record.stats.mean, record.stats.samples)
+    );
+
+    if let Some(failure) = record.status.failure() {
+        print!("\r");
+        println!("{}: ✖ {failure}             ", record.phase);
+        return;
+    }
+
+    match record.phase {
+        Phase::Parse => {
+            print!("\r");
+            println!("{}:{duration_str}", record.phase);
+        }
+        Phase::Part(_) => print_result(&record.answer, &record.phase.to_string(), &duration_str),
+    }
+
+    print_stats(&record.stats);
+
+    if let Some(memory) = &record.memory {
+        println!("  {ANSI_ITALIC}{memory}{ANSI_RESET}");
+    }
 }
 
 fn format_duration(duration: &Duration, samples: u128) -> String {
@@ -94,6 +348,23 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
+fn print_stats(stats: &Stats) {
+    if stats.samples <= 1 {
+        return;
+    }
+
+    let mut line = format!(
+        "  {ANSI_ITALIC}median {:.1?} · min {:.1?} · max {:.1?} · σ {:.1?} · p95 {:.1?} · p99 {:.1?}",
+        stats.median, stats.min, stats.max, stats.std_dev, stats.p95, stats.p99
+    );
+
+    if stats.outliers > 0 {
+        line.push_str(&format!(" · {} outliers rejected", stats.outliers));
+    }
+
+    println!("{line}{ANSI_RESET}");
+}
+
 fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
@@ -131,12 +402,14 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
+///  2. a session cookie is configured.
+///  3. the answer passes the checks against previous submissions, and the user confirms it.
+///     `--force` skips the confirmation and allows text answers.
 fn submit_result<T: Display>(
     result: T,
     day: Day,
     part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+) -> Option<Result<Submission, aoc_client::AocClientError>> {
     let args: Vec<String> = env::args().collect();
 
     if !args.contains(&"--submit".into()) {
@@ -159,11 +432,55 @@ fn submit_result<T: Display>(
         return None;
     }
 
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+    let answer = result.to_string();
+    let force = args.contains(&"--force".into());
+
+    if let Err(refusal) = submissions::check(&submissions::read(day), part, &answer, force) {
+        eprintln!("Not submitting: {refusal}");
+        return None;
+    }
+
+    if !force && !confirm(&format!("Submit `{answer}` for day {day}, part {part}?")) {
+        println!("Not submitting.");
+        return None;
+    }
+
+    println!("Submitting result...");
+    let submission = submissions::submit(day, part, &answer);
+    match &submission {
+        Ok(submission) => print_verdict(day, submission),
+        Err(e) => eprintln!("Failed to submit result: {e}"),
+    }
+    Some(submission)
+}
+
+/// Ask a yes/no question on stdin. Anything but `y` or `yes` is a no.
+fn confirm(question: &str) -> bool {
+    print!("{question} [y/N] ");
+    let _ = stdout().flush();
+
+    let mut line = String::new();
+    if stdin().read_line(&mut line).is_err() {
+        return false;
     }
+    matches!(line.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Print the verdict of a submission. A correct answer earns the star of the part.
+fn print_verdict(day: Day, submission: &Submission) {
+    let color = match submission.verdict {
+        Verdict::Correct => ANSI_GREEN,
+        Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong => ANSI_RED,
+        _ => "",
+    };
+
+    println!(
+        "{ANSI_BOLD}Verdict:{ANSI_RESET} {color}{}{ANSI_RESET}",
+        submission.verdict
+    );
+    println!("{ANSI_ITALIC}{}{ANSI_RESET}", submission.message);
 
-    println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    if submission.verdict == Verdict::Correct {
+        add_star(day, submission.part);
+    }
 }
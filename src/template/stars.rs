@@ -0,0 +1,209 @@
+/// Record of the solved parts of each day, rendered as the stars table in the readme.
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Error, ErrorKind},
+    str::FromStr,
+};
+use tinyjson::JsonValue;
+
+use crate::template::json;
+use crate::template::paths::paths;
+use crate::template::Day;
+
+/// Represents the solved parts of a single day.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DayStars {
+    pub day: Day,
+    pub part_1: bool,
+    pub part_2: bool,
+}
+
+/// Represents the solved parts of a set of days.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Stars {
+    pub data: Vec<DayStars>,
+}
+
+impl Stars {
+    /// Dehydrate stars to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        fs::write(paths().stars(), json::format_sorted(&json))
+    }
+
+    /// Rehydrate stars from a JSON file. If not present, returns empty stars.
+    /// Fails if the file can not be read or parsed.
+    pub fn read_from_file() -> Result<Self, String> {
+        let path = paths().stars();
+        let stars = match fs::read_to_string(&path) {
+            Ok(s) => Stars::try_from(s),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Stars::default()),
+            Err(e) => Err(e.to_string()),
+        };
+        stars.map_err(|e| format!("Failed to read stars from \"{}\": {e}", path.display()))
+    }
+
+    /// Whether stars were stored before.
+    pub fn is_stored() -> bool {
+        paths().stars().exists()
+    }
+
+    /// Add the star of a part of a day. Returns `false` if the part was solved before.
+    pub fn add(&mut self, day: Day, part: u8) -> bool {
+        let index = match self.data.iter().position(|s| s.day == day) {
+            Some(index) => index,
+            None => {
+                self.data.push(DayStars {
+                    day,
+                    part_1: false,
+                    part_2: false,
+                });
+                self.data.sort_unstable_by_key(|s| s.day);
+                self.data.iter().position(|s| s.day == day).unwrap()
+            }
+        };
+
+        let star = match part {
+            1 => &mut self.data[index].part_1,
+            2 => &mut self.data[index].part_2,
+            _ => return false,
+        };
+
+        !std::mem::replace(star, true)
+    }
+
+    /// Total number of stars.
+    pub fn count(&self) -> usize {
+        self.data
+            .iter()
+            .map(|s| usize::from(s.part_1) + usize::from(s.part_2))
+            .sum()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Stars> for JsonValue {
+    fn from(value: Stars) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Stars {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Stars {
+            data: json_data
+                .iter()
+                .map(DayStars::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&DayStars> for JsonValue {
+    fn from(value: &DayStars) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part_1".into(), JsonValue::Boolean(value.part_1));
+        map.insert("part_2".into(), JsonValue::Boolean(value.part_2));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for DayStars {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected stars to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected stars.day to be a Day struct.")?;
+
+        let part = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<bool>())
+                .copied()
+                .ok_or(format!("Expected stars.{key} to be a boolean."))
+        };
+
+        Ok(DayStars {
+            day,
+            part_1: part("part_1")?,
+            part_2: part("part_2")?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use tinyjson::JsonValue;
+
+    use super::Stars;
+    use crate::day;
+
+    #[test]
+    fn adds_stars() {
+        let mut stars = Stars::default();
+        assert!(stars.add(day!(5), 1));
+        assert!(stars.add(day!(2), 2));
+        assert!(!stars.add(day!(5), 1));
+        assert!(stars.add(day!(5), 2));
+        assert!(!stars.add(day!(5), 3));
+
+        assert_eq!(stars.data.len(), 2);
+        assert_eq!(stars.data[0].day, day!(2));
+        assert!(!stars.data[0].part_1);
+        assert!(stars.data[1].part_1 && stars.data[1].part_2);
+        assert_eq!(stars.count(), 3);
+    }
+
+    #[test]
+    fn roundtrips_stars() {
+        let mut stars = Stars::default();
+        stars.add(day!(1), 1);
+        stars.add(day!(3), 2);
+
+        let json = JsonValue::from(stars.clone()).stringify().unwrap();
+        let parsed = Stars::try_from(json).unwrap();
+        assert_eq!(parsed.data, stars.data);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_stars() {
+        let json = r#"{ "data": [{ "day": "01", "part_1": "yes" }] }"#.to_string();
+        Stars::try_from(json).unwrap();
+    }
+}
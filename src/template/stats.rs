@@ -0,0 +1,148 @@
+/// Summary statistics over the samples collected while benching a solution part.
+use std::time::Duration;
+
+/// Multiplier of the interquartile range used for the outlier fences (Tukey's method).
+const OUTLIER_FENCE: f64 = 1.5;
+
+/// Describes the distribution of the per-iteration durations of a bench run.
+/// Outliers are rejected before any of the values are computed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    /// The number of iterations that were run, including the rejected outliers.
+    pub samples: u128,
+    pub outliers: u128,
+    pub mean: Duration,
+    pub median: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub std_dev: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+impl Stats {
+    /// Stats for a part that was executed exactly once.
+    pub fn single(duration: Duration) -> Self {
+        Self {
+            samples: 1,
+            outliers: 0,
+            mean: duration,
+            median: duration,
+            min: duration,
+            max: duration,
+            std_dev: Duration::ZERO,
+            p95: duration,
+            p99: duration,
+        }
+    }
+
+    /// Compute the stats for a set of samples. Returns `None` if there are no samples.
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let kept = reject_outliers(&sorted);
+
+        let nanos: Vec<f64> = kept.iter().map(|d| d.as_nanos() as f64).collect();
+        let mean = nanos.iter().sum::<f64>() / nanos.len() as f64;
+        let variance = nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / nanos.len() as f64;
+
+        Some(Self {
+            samples: sorted.len() as u128,
+            outliers: (sorted.len() - kept.len()) as u128,
+            mean: Duration::from_secs_f64(mean / 1e9),
+            median: percentile(kept, 50.0),
+            min: kept[0],
+            max: kept[kept.len() - 1],
+            std_dev: Duration::from_secs_f64(variance.sqrt() / 1e9),
+            p95: percentile(kept, 95.0),
+            p99: percentile(kept, 99.0),
+        })
+    }
+}
+
+/// Nearest-rank percentile of an already sorted, non-empty slice.
+fn percentile(sorted: &[Duration], pct: f64) -> Duration {
+    let rank = (pct / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Drop the samples outside of the interquartile fences of an already sorted slice.
+/// Too few samples to compute quartiles are returned as-is.
+fn reject_outliers(sorted: &[Duration]) -> &[Duration] {
+    if sorted.len() < 4 {
+        return sorted;
+    }
+
+    let q1 = percentile(sorted, 25.0).as_nanos() as f64;
+    let q3 = percentile(sorted, 75.0).as_nanos() as f64;
+    let iqr = q3 - q1;
+    let lower = q1 - OUTLIER_FENCE * iqr;
+    let upper = q3 + OUTLIER_FENCE * iqr;
+
+    let start = sorted.partition_point(|d| (d.as_nanos() as f64) < lower);
+    let end = sorted.partition_point(|d| (d.as_nanos() as f64) <= upper);
+
+    &sorted[start..end]
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::Stats;
+
+    fn nanos(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|x| Duration::from_nanos(*x)).collect()
+    }
+
+    #[test]
+    fn handles_empty_samples() {
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn computes_stats() {
+        let stats = Stats::from_samples(&nanos(&[40, 10, 30, 20])).unwrap();
+        assert_eq!(stats.samples, 4);
+        assert_eq!(stats.outliers, 0);
+        assert_eq!(stats.mean, Duration::from_nanos(25));
+        assert_eq!(stats.median, Duration::from_nanos(20));
+        assert_eq!(stats.min, Duration::from_nanos(10));
+        assert_eq!(stats.max, Duration::from_nanos(40));
+        assert_eq!(stats.p95, Duration::from_nanos(40));
+        assert_eq!(stats.p99, Duration::from_nanos(40));
+        assert_eq!(stats.std_dev.as_nanos(), 11);
+    }
+
+    #[test]
+    fn rejects_outliers() {
+        let stats = Stats::from_samples(&nanos(&[10, 11, 10, 12, 11, 10, 500])).unwrap();
+        assert_eq!(stats.samples, 7);
+        assert_eq!(stats.outliers, 1);
+        assert_eq!(stats.max, Duration::from_nanos(12));
+    }
+
+    #[test]
+    fn computes_percentiles() {
+        let samples: Vec<u64> = (1..=100).collect();
+        let stats = Stats::from_samples(&nanos(&samples)).unwrap();
+        assert_eq!(stats.median, Duration::from_nanos(50));
+        assert_eq!(stats.p95, Duration::from_nanos(95));
+        assert_eq!(stats.p99, Duration::from_nanos(99));
+    }
+
+    #[test]
+    fn handles_single_run() {
+        let stats = Stats::single(Duration::from_millis(2));
+        assert_eq!(stats.samples, 1);
+        assert_eq!(stats.median, Duration::from_millis(2));
+        assert_eq!(stats.std_dev, Duration::ZERO);
+    }
+}
@@ -0,0 +1,427 @@
+/// Verdicts of submitted answers, logged per day in `data/submissions/DD.jsonl`.
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs::{self, OpenOptions},
+    io::{Error, Write},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tinyjson::JsonValue;
+
+use crate::template::aoc_client::{AocClientError, Client};
+use crate::template::json;
+use crate::template::paths::paths;
+use crate::template::Day;
+
+/// How the server responded to a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// The answer is wrong, without a hint in which direction.
+    Wrong,
+    /// The part was solved before, so the answer was not checked.
+    AlreadySolved,
+    /// An answer was submitted too recently. Contains the time left to wait.
+    RateLimited(Duration),
+    /// The response could not be classified.
+    Unknown,
+}
+
+impl Verdict {
+    /// Classify the message of a response.
+    pub fn from_message(message: &str) -> Self {
+        if message.contains("That's the right answer") {
+            Verdict::Correct
+        } else if message.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if message.contains("your answer is too low") {
+            Verdict::TooLow
+        } else if message.contains("That's not the right answer") {
+            Verdict::Wrong
+        } else if message.contains("You don't seem to be solving the right level") {
+            Verdict::AlreadySolved
+        } else if message.contains("You gave an answer too recently") {
+            Verdict::RateLimited(parse_wait(message).unwrap_or_default())
+        } else {
+            Verdict::Unknown
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too_high",
+            Verdict::TooLow => "too_low",
+            Verdict::Wrong => "wrong",
+            Verdict::AlreadySolved => "already_solved",
+            Verdict::RateLimited(_) => "rate_limited",
+            Verdict::Unknown => "unknown",
+        }
+    }
+
+    fn from_key(key: &str, wait: Duration) -> Option<Self> {
+        match key {
+            "correct" => Some(Verdict::Correct),
+            "too_high" => Some(Verdict::TooHigh),
+            "too_low" => Some(Verdict::TooLow),
+            "wrong" => Some(Verdict::Wrong),
+            "already_solved" => Some(Verdict::AlreadySolved),
+            "rate_limited" => Some(Verdict::RateLimited(wait)),
+            "unknown" => Some(Verdict::Unknown),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "✔ correct"),
+            Verdict::TooHigh => write!(f, "✘ wrong, too high"),
+            Verdict::TooLow => write!(f, "✘ wrong, too low"),
+            Verdict::Wrong => write!(f, "✘ wrong"),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+            Verdict::RateLimited(wait) => write!(f, "rate limited, wait {wait:?}"),
+            Verdict::Unknown => write!(f, "unknown response"),
+        }
+    }
+}
+
+/// Parse the time left to wait from a message like `You have 1m 5s left to wait.`
+fn parse_wait(message: &str) -> Option<Duration> {
+    let (_, rest) = message.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+
+    wait.split_whitespace()
+        .map(|part| {
+            let (value, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+            let value: u64 = value.parse().ok()?;
+            match unit {
+                "h" => Some(value * 3600),
+                "m" => Some(value * 60),
+                "s" => Some(value),
+                _ => None,
+            }
+        })
+        .sum::<Option<u64>>()
+        .map(Duration::from_secs)
+}
+
+/// A submitted answer and the response to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Submission {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    /// The message of the response.
+    pub message: String,
+}
+
+/// Submit an answer and log the verdict.
+pub fn submit(day: Day, part: u8, answer: &str) -> Result<Submission, AocClientError> {
+    let message = Client::from_env()?.submit(day, part, answer)?;
+
+    let submission = Submission {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_secs()),
+        part,
+        answer: answer.into(),
+        verdict: Verdict::from_message(&message),
+        message,
+    };
+
+    if let Err(e) = append(day, &submission) {
+        eprintln!("Failed to log submission: {e}");
+    }
+
+    Ok(submission)
+}
+
+/// Read the submissions of a day. Lines that can not be parsed are skipped.
+pub fn read(day: Day) -> Vec<Submission> {
+    fs::read_to_string(paths().submissions(day))
+        .map(|s| {
+            s.lines()
+                .filter_map(|line| Submission::try_from(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Append a submission to the log of a day.
+pub fn append(day: Day, submission: &Submission) -> Result<(), Error> {
+    let path = paths().submissions(day);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let line = json::stringify_sorted(&JsonValue::from(submission));
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Why an answer is not submitted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Refusal {
+    Empty,
+    /// The answer is not a number. Puzzles with text answers need `--force`.
+    NotNumeric,
+    /// The answer was checked before.
+    Repeated(Verdict),
+    /// The part was solved with another answer.
+    Solved(String),
+    /// The answer is at least an answer that was too high.
+    AboveBound(i128),
+    /// The answer is at most an answer that was too low.
+    BelowBound(i128),
+}
+
+impl Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Refusal::Empty => write!(f, "the answer is empty."),
+            Refusal::NotNumeric => write!(
+                f,
+                "the answer is not a number. Pass `--force` if the puzzle expects text."
+            ),
+            Refusal::Repeated(verdict) => {
+                write!(f, "the answer was submitted before ({verdict}).")
+            }
+            Refusal::Solved(answer) => {
+                write!(f, "the part was already solved with `{answer}`.")
+            }
+            Refusal::AboveBound(high) => write!(f, "`{high}` was already too high."),
+            Refusal::BelowBound(low) => write!(f, "`{low}` was already too low."),
+        }
+    }
+}
+
+/// Check an answer against the previous submissions of its part before sending it.
+/// With `force`, text answers are allowed.
+pub fn check(history: &[Submission], part: u8, answer: &str, force: bool) -> Result<(), Refusal> {
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return Err(Refusal::Empty);
+    }
+
+    let number = answer.parse::<i128>().ok();
+    if number.is_none() && !force {
+        return Err(Refusal::NotNumeric);
+    }
+
+    // Rate limited and unclassified submissions were not checked, so they do not count.
+    let checked = history.iter().filter(|x| {
+        x.part == part
+            && matches!(
+                x.verdict,
+                Verdict::Correct | Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong
+            )
+    });
+
+    let mut high: Option<i128> = None;
+    let mut low: Option<i128> = None;
+
+    for submission in checked {
+        if submission.answer.trim() == answer {
+            return Err(Refusal::Repeated(submission.verdict));
+        }
+
+        let value = submission.answer.trim().parse::<i128>().ok();
+        match (submission.verdict, value) {
+            (Verdict::Correct, _) => return Err(Refusal::Solved(submission.answer.clone())),
+            (Verdict::TooHigh, Some(x)) => high = Some(high.map_or(x, |h| h.min(x))),
+            (Verdict::TooLow, Some(x)) => low = Some(low.map_or(x, |l| l.max(x))),
+            _ => {}
+        }
+    }
+
+    match number {
+        Some(x) if high.is_some_and(|h| x >= h) => Err(Refusal::AboveBound(high.unwrap())),
+        Some(x) if low.is_some_and(|l| x <= l) => Err(Refusal::BelowBound(low.unwrap())),
+        _ => Ok(()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Submission> for JsonValue {
+    fn from(value: &Submission) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(value.timestamp as f64),
+        );
+        map.insert("part".into(), JsonValue::Number(value.part.into()));
+        map.insert("answer".into(), JsonValue::String(value.answer.clone()));
+        map.insert(
+            "verdict".into(),
+            JsonValue::String(value.verdict.key().into()),
+        );
+        map.insert(
+            "wait_seconds".into(),
+            match value.verdict {
+                Verdict::RateLimited(wait) => JsonValue::Number(wait.as_secs() as f64),
+                _ => JsonValue::Null,
+            },
+        );
+        map.insert("message".into(), JsonValue::String(value.message.clone()));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&str> for Submission {
+    type Error = String;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(line).or(Err("not valid JSON."))?;
+        let map = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected submission to be a JSON object.")?;
+
+        let number = |key: &str| {
+            map.get(key)
+                .and_then(|v| v.get::<f64>())
+                .copied()
+                .ok_or(format!("Expected submission.{key} to be a number."))
+        };
+        let string = |key: &str| {
+            map.get(key)
+                .and_then(|v| v.get::<String>())
+                .cloned()
+                .ok_or(format!("Expected submission.{key} to be a string."))
+        };
+
+        let wait = Duration::from_secs(number("wait_seconds").unwrap_or_default() as u64);
+        let verdict = Verdict::from_key(&string("verdict")?, wait)
+            .ok_or("Expected submission.verdict to be a known verdict.")?;
+
+        Ok(Submission {
+            timestamp: number("timestamp")? as u64,
+            part: number("part")? as u8,
+            answer: string("answer")?,
+            verdict,
+            message: string("message")?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use tinyjson::JsonValue;
+
+    use super::{check, Refusal, Submission, Verdict};
+
+    fn submission(part: u8, answer: &str, verdict: Verdict) -> Submission {
+        Submission {
+            timestamp: 0,
+            part,
+            answer: answer.into(),
+            verdict,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn classifies_messages() {
+        let cases = [
+            ("That's the right answer! You are one gold star closer to saving your vacation.", Verdict::Correct),
+            ("That's not the right answer; your answer is too high. If you're stuck, ...", Verdict::TooHigh),
+            ("That's not the right answer; your answer is too low. Please wait one minute.", Verdict::TooLow),
+            ("That's not the right answer. If you're stuck, make sure you're using the full input data.", Verdict::Wrong),
+            ("You don't seem to be solving the right level. Did you already complete it?", Verdict::AlreadySolved),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again. You have 1m 5s left to wait.",
+                Verdict::RateLimited(Duration::from_secs(65)),
+            ),
+            ("You gave an answer too recently. You have 35s left to wait.", Verdict::RateLimited(Duration::from_secs(35))),
+            ("Something else entirely.", Verdict::Unknown),
+        ];
+
+        for (message, verdict) in cases {
+            assert_eq!(Verdict::from_message(message), verdict, "{message}");
+        }
+    }
+
+    #[test]
+    fn roundtrips_submissions() {
+        for verdict in [
+            Verdict::TooLow,
+            Verdict::RateLimited(Duration::from_secs(35)),
+        ] {
+            let submission = Submission {
+                timestamp: 1_733_011_200,
+                part: 2,
+                answer: "42".into(),
+                verdict,
+                message: "a \"message\"".into(),
+            };
+
+            let line = JsonValue::from(&submission).stringify().unwrap();
+            assert_eq!(Submission::try_from(line.as_str()), Ok(submission));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_verdicts() {
+        let line =
+            r#"{ "timestamp": 0, "part": 1, "answer": "1", "verdict": "maybe", "message": "" }"#;
+        assert!(Submission::try_from(line).is_err());
+    }
+
+    #[test]
+    fn refuses_invalid_answers() {
+        assert_eq!(check(&[], 1, " ", false), Err(Refusal::Empty));
+        assert_eq!(check(&[], 1, "ABC", false), Err(Refusal::NotNumeric));
+        assert_eq!(check(&[], 1, "ABC", true), Ok(()));
+        assert_eq!(check(&[], 1, "-42", false), Ok(()));
+    }
+
+    #[test]
+    fn refuses_repeats_and_answers_out_of_bounds() {
+        let history = [
+            submission(1, "100", Verdict::TooHigh),
+            submission(1, "80", Verdict::TooHigh),
+            submission(1, "20", Verdict::TooLow),
+            submission(1, "50", Verdict::Wrong),
+            submission(1, "60", Verdict::RateLimited(Duration::from_secs(30))),
+            submission(2, "10", Verdict::Correct),
+        ];
+
+        assert_eq!(
+            check(&history, 1, "50", false),
+            Err(Refusal::Repeated(Verdict::Wrong))
+        );
+        assert_eq!(
+            check(&history, 1, "90", false),
+            Err(Refusal::AboveBound(80))
+        );
+        assert_eq!(
+            check(&history, 1, "80", false),
+            Err(Refusal::Repeated(Verdict::TooHigh))
+        );
+        assert_eq!(
+            check(&history, 1, "15", false),
+            Err(Refusal::BelowBound(20))
+        );
+        assert_eq!(check(&history, 1, "60", false), Ok(()));
+        assert_eq!(check(&history, 1, "21", false), Ok(()));
+        assert_eq!(
+            check(&history, 2, "11", false),
+            Err(Refusal::Solved("10".into()))
+        );
+    }
+}
@@ -1,17 +1,107 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    io::{Error, ErrorKind},
+    str::FromStr,
+    time::Duration,
+};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
+use crate::template::environment::Environment;
+use crate::template::json;
+use crate::template::memory::{self, Memory};
+use crate::template::paths::paths;
+use crate::template::{parse_duration, Day};
 
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+/// Version of the format timings are stored in.
+/// Version 1 stored the timing of each part as a formatted string and had no `version` key.
+const TIMINGS_VERSION: u8 = 2;
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
 pub struct Timing {
     pub day: Day,
-    pub part_1: Option<String>,
-    pub part_2: Option<String>,
+    pub parse: Option<PhaseTiming>,
+    pub part_1: Option<PhaseTiming>,
+    pub part_2: Option<PhaseTiming>,
     pub total_nanos: f64,
+    /// Parts that panicked or timed out.
+    pub failures: Vec<Failure>,
+    /// Combined heap usage of all phases, if allocations were counted.
+    pub memory: Option<Memory>,
+}
+
+/// The mean execution time of a phase of a day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PhaseTiming {
+    pub nanos: u64,
+    /// Number of samples the mean was computed from. Unknown for migrated timings.
+    pub samples: Option<u128>,
+}
+
+impl PhaseTiming {
+    pub fn duration(&self) -> Duration {
+        Duration::from_nanos(self.nanos)
+    }
+}
+
+impl From<Duration> for PhaseTiming {
+    fn from(value: Duration) -> Self {
+        Self {
+            nanos: value.as_nanos() as u64,
+            samples: None,
+        }
+    }
+}
+
+impl Display for PhaseTiming {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.1?}", self.duration())
+    }
+}
+
+/// Why a part of a day did not produce a timing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub part: u8,
+    pub reason: String,
+}
+
+/// How new timings of a day are merged with the stored ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep the newest run.
+    #[default]
+    Latest,
+    /// Keep the fastest timing of each phase.
+    Best,
+    /// Keep the median timing of each phase across the given number of most recent runs.
+    Median(usize),
+}
+
+/// Number of runs `--merge median` takes the median of, if not specified.
+const MEDIAN_RUNS: usize = 5;
+
+impl FromStr for MergeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "latest" => Ok(MergeStrategy::Latest),
+            None if s == "best" => Ok(MergeStrategy::Best),
+            None if s == "median" => Ok(MergeStrategy::Median(MEDIAN_RUNS)),
+            Some(("median", runs)) => match runs.parse() {
+                Ok(runs) if runs > 0 => Ok(MergeStrategy::Median(runs)),
+                _ => Err(format!(
+                    "expected a positive number of runs, found `{runs}`."
+                )),
+            },
+            _ => Err(format!(
+                "unknown merge strategy `{s}`, expected `latest`, `best` or `median[:runs]`."
+            )),
+        }
+    }
 }
 
 /// Represents benchmark times for a set of days.
@@ -19,25 +109,33 @@ pub struct Timing {
 #[derive(Clone, Debug, Default)]
 pub struct Timings {
     pub data: Vec<Timing>,
+    /// The environment the timings were measured in, if known.
+    pub environment: Option<Environment>,
 }
 
 impl Timings {
     /// Dehydrate timings to a JSON file.
     pub fn store_file(&self) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
-        json.format_to(&mut file)
+        fs::write(&paths().timings, json::format_sorted(&json))
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
-    pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
-            .map_err(|x| x.to_string())
-            .and_then(Timings::try_from)
-            .unwrap_or_default()
+    /// Timings stored in an older format are migrated to the current one.
+    /// Fails if the file can not be read or parsed, or was stored by a newer version.
+    pub fn read_from_file() -> Result<Self, String> {
+        let path = &paths().timings;
+        let timings = match fs::read_to_string(path) {
+            Ok(s) => Timings::try_from(s),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Timings::default()),
+            Err(e) => Err(e.to_string()),
+        };
+        timings.map_err(|e| format!("Failed to read timings from \"{}\": {e}", path.display()))
     }
 
     /// Merge two sets of timings, overwriting `self` with `other` if present.
+    /// If the result mixes timings measured in different environments, the stored environment is
+    /// kept, since it describes the days that were not run again, and a warning is printed.
     pub fn merge(&self, new: &Self) -> Self {
         let mut data: Vec<Timing> = vec![];
 
@@ -51,8 +149,70 @@ impl Timings {
             }
         }
 
+        let is_mixed = self
+            .data
+            .iter()
+            .any(|t| !new.data.iter().any(|n| n.day == t.day));
+
+        let environment = match (&self.environment, &new.environment) {
+            _ if !is_mixed => new.environment.clone().or(self.environment.clone()),
+            (Some(old_env), Some(new_env)) if old_env.is_comparable(new_env) => {
+                new.environment.clone()
+            }
+            (Some(old_env), Some(new_env)) => {
+                eprintln!(
+                    "Warning: merging timings from different environments, keeping the stored one.\n  stored: {old_env}\n  new:    {new_env}"
+                );
+                self.environment.clone()
+            }
+            _ => self.environment.clone(),
+        };
+
         data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
-        Timings { data }
+        Timings { data, environment }
+    }
+
+    /// Merge new timings using a strategy.
+    /// `history` are the previously stored runs, oldest first, which [`MergeStrategy::Median`] uses.
+    pub fn merge_with(&self, new: &Self, strategy: MergeStrategy, history: &[Timings]) -> Self {
+        let data = match strategy {
+            MergeStrategy::Latest => new.data.clone(),
+            MergeStrategy::Best => new
+                .data
+                .iter()
+                .map(
+                    |timing| match self.data.iter().find(|t| t.day == timing.day) {
+                        Some(stored) => Timing::combine(&[stored, timing], |phases| {
+                            *phases.iter().min_by_key(|x| x.nanos).unwrap()
+                        }),
+                        None => timing.clone(),
+                    },
+                )
+                .collect(),
+            MergeStrategy::Median(runs) => new
+                .data
+                .iter()
+                .map(|timing| {
+                    let mut timings: Vec<&Timing> = history
+                        .iter()
+                        .filter_map(|h| h.data.iter().find(|t| t.day == timing.day))
+                        .collect();
+                    timings.push(timing);
+                    let recent = &timings[timings.len().saturating_sub(runs)..];
+
+                    Timing::combine(recent, |phases| {
+                        let mut phases = phases.to_vec();
+                        phases.sort_unstable_by_key(|x| x.nanos);
+                        phases[(phases.len() - 1) / 2]
+                    })
+                })
+                .collect(),
+        };
+
+        self.merge(&Timings {
+            data,
+            environment: new.environment.clone(),
+        })
     }
 
     /// Sum up total duration of timings as millis.
@@ -67,16 +227,69 @@ impl Timings {
     }
 }
 
+impl Timing {
+    /// Combine timings of the same day, picking each phase from the timings that measured it.
+    /// Failures and memory are taken from the last timing.
+    fn combine(timings: &[&Timing], pick: impl Fn(&[PhaseTiming]) -> PhaseTiming) -> Timing {
+        let last = timings.last().expect("at least one timing to combine");
+
+        let phase = |get: fn(&Timing) -> Option<PhaseTiming>| {
+            let phases: Vec<PhaseTiming> = timings.iter().filter_map(|t| get(t)).collect();
+            (!phases.is_empty()).then(|| pick(&phases))
+        };
+
+        let parse = phase(|t| t.parse);
+        let part_1 = phase(|t| t.part_1);
+        let part_2 = phase(|t| t.part_2);
+
+        let total_nanos = [parse, part_1, part_2]
+            .iter()
+            .flatten()
+            .map(|x| x.nanos as f64)
+            .sum();
+
+        // a failure is only kept if no run measured the part.
+        let failures = last
+            .failures
+            .iter()
+            .filter(|f| match f.part {
+                1 => part_1.is_none(),
+                2 => part_2.is_none(),
+                _ => true,
+            })
+            .cloned()
+            .collect();
+
+        Timing {
+            day: last.day,
+            parse,
+            part_1,
+            part_2,
+            total_nanos,
+            failures,
+            memory: last.memory,
+        }
+    }
+}
+
 /* -------------------------------------------------------------------------- */
 
 impl From<Timings> for JsonValue {
     fn from(value: Timings) -> Self {
         let mut map: HashMap<String, JsonValue> = HashMap::new();
 
+        map.insert("version".into(), JsonValue::Number(TIMINGS_VERSION.into()));
         map.insert(
             "data".into(),
             JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
         );
+        map.insert(
+            "environment".into(),
+            value
+                .environment
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
 
         JsonValue::Object(map)
     }
@@ -87,10 +300,33 @@ impl TryFrom<String> for Timings {
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+        Timings::try_from(&json)
+    }
+}
 
-        let json_data = json
+impl TryFrom<&JsonValue> for Timings {
+    type Error = String;
+
+    fn try_from(json: &JsonValue) -> Result<Self, Self::Error> {
+        let json_map = json
             .get::<HashMap<String, JsonValue>>()
-            .ok_or("expected JSON document to be an object.")?
+            .ok_or("expected JSON document to be an object.")?;
+
+        let version = match json_map.get("version") {
+            Some(v) => v
+                .get::<f64>()
+                .map(|x| *x as u8)
+                .ok_or("expected `json.version` to be a number.")?,
+            None => 1,
+        };
+
+        if version > TIMINGS_VERSION {
+            return Err(format!(
+                "timings were stored in version {version}, which is newer than the supported version {TIMINGS_VERSION}."
+            ));
+        }
+
+        let json_data = json_map
             .get("data")
             .ok_or("expected JSON document to have key `data`.")?
             .get::<Vec<JsonValue>>()
@@ -99,8 +335,13 @@ impl TryFrom<String> for Timings {
         Ok(Timings {
             data: json_data
                 .iter()
-                .map(Timing::try_from)
+                .map(|x| Timing::from_json(x, version))
                 .collect::<Result<_, _>>()?,
+            // NOTE: timings stored before the environment was recorded do not have this key.
+            environment: match json_map.get("environment") {
+                Some(v) if !v.is_null() => Some(Environment::try_from(v)?),
+                _ => None,
+            },
         })
     }
 }
@@ -114,33 +355,30 @@ impl From<&Timing> for JsonValue {
         map.insert("day".into(), JsonValue::String(value.day.to_string()));
         map.insert("total_nanos".into(), JsonValue::Number(value.total_nanos));
 
-        let part_1 = value.part_1.clone().map(JsonValue::String);
-        let part_2 = value.part_2.clone().map(JsonValue::String);
-
-        map.insert(
-            "part_1".into(),
-            match part_1 {
-                Some(x) => x,
-                None => JsonValue::Null,
-            },
-        );
+        for (key, phase) in [
+            ("parse", &value.parse),
+            ("part_1", &value.part_1),
+            ("part_2", &value.part_2),
+        ] {
+            map.insert(
+                key.into(),
+                phase.as_ref().map_or(JsonValue::Null, JsonValue::from),
+            );
+        }
 
         map.insert(
-            "part_2".into(),
-            match part_2 {
-                Some(x) => x,
-                None => JsonValue::Null,
-            },
+            "failures".into(),
+            JsonValue::Array(value.failures.iter().map(JsonValue::from).collect()),
         );
+        map.insert("memory".into(), memory::to_json(value.memory.as_ref()));
 
         JsonValue::Object(map)
     }
 }
 
-impl TryFrom<&JsonValue> for Timing {
-    type Error = String;
-
-    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+impl Timing {
+    /// Parses a timing stored in the given version of the format.
+    fn from_json(value: &JsonValue, version: u8) -> Result<Self, String> {
         let json = value
             .get::<HashMap<String, JsonValue>>()
             .ok_or("Expected timing to be a JSON object.")?;
@@ -151,26 +389,126 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|day| Day::from_str(day).ok())
             .ok_or("Expected timing.day to be a Day struct.")?;
 
-        let part_1 = json
-            .get("part_1")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_1 to be null or string.")?;
-
-        let part_2 = json
-            .get("part_2")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_2 to be null or string.")?;
+        let phase = |key: &str| -> Result<Option<PhaseTiming>, String> {
+            match json.get(key) {
+                Some(v) if v.is_null() => Ok(None),
+                Some(v) if version == 1 => v
+                    .get::<String>()
+                    .and_then(|x| parse_duration(x).ok())
+                    .map(|x| Some(PhaseTiming::from(x)))
+                    .ok_or(format!("Expected timing.{key} to be null or a duration.")),
+                Some(v) => PhaseTiming::try_from(v).map(Some),
+                // NOTE: timings stored before the parse phase was introduced do not have this key.
+                None if key == "parse" => Ok(None),
+                None => Err(format!("Expected timing.{key} to be present.")),
+            }
+        };
 
         let total_nanos = json
             .get("total_nanos")
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        // NOTE: timings stored before failures were recorded do not have this key.
+        let failures = match json.get("failures") {
+            Some(v) => v
+                .get::<Vec<JsonValue>>()
+                .ok_or("Expected timing.failures to be an array.")?
+                .iter()
+                .map(Failure::try_from)
+                .collect::<Result<_, _>>()?,
+            None => vec![],
+        };
+
         Ok(Timing {
             day,
-            part_1: part_1.cloned(),
-            part_2: part_2.cloned(),
+            parse: phase("parse")?,
+            part_1: phase("part_1")?,
+            part_2: phase("part_2")?,
             total_nanos,
+            failures,
+            memory: memory::from_json(json.get("memory"))?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&PhaseTiming> for JsonValue {
+    fn from(value: &PhaseTiming) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("nanos".into(), JsonValue::Number(value.nanos as f64));
+        map.insert(
+            "samples".into(),
+            value
+                .samples
+                .map_or(JsonValue::Null, |x| JsonValue::Number(x as f64)),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for PhaseTiming {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected phase timing to be a JSON object.")?;
+
+        let nanos = json
+            .get("nanos")
+            .and_then(|v| v.get::<f64>())
+            .ok_or("Expected phase timing.nanos to be a number.")?;
+
+        let samples = json
+            .get("samples")
+            .and_then(|v| v.get::<f64>())
+            .map(|x| *x as u128);
+
+        Ok(PhaseTiming {
+            nanos: *nanos as u64,
+            samples,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Failure> for JsonValue {
+    fn from(value: &Failure) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("part".into(), JsonValue::Number(value.part.into()));
+        map.insert("reason".into(), JsonValue::String(value.reason.clone()));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Failure {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected failure to be a JSON object.")?;
+
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .ok_or("Expected failure.part to be a number.")?;
+
+        let reason = json
+            .get("reason")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected failure.reason to be a string.")?;
+
+        Ok(Failure {
+            part: *part as u8,
+            reason: reason.clone(),
         })
     }
 }
@@ -179,6 +517,8 @@ impl TryFrom<&JsonValue> for Timing {
 
 #[cfg(feature = "test_lib")]
 mod tests {
+    use std::time::Duration;
+
     use crate::day;
 
     use super::{Timing, Timings};
@@ -188,23 +528,33 @@ mod tests {
             data: vec![
                 Timing {
                     day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
+                    parse: None,
+                    part_1: Some(Duration::from_millis(10).into()),
+                    part_2: Some(Duration::from_millis(20).into()),
                     total_nanos: 3e+10,
+                    failures: vec![],
+                    memory: None,
                 },
                 Timing {
                     day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
+                    parse: None,
+                    part_1: Some(Duration::from_millis(30).into()),
+                    part_2: Some(Duration::from_millis(40).into()),
                     total_nanos: 7e+10,
+                    failures: vec![],
+                    memory: None,
                 },
                 Timing {
                     day: day!(4),
-                    part_1: Some("40ms".into()),
+                    parse: None,
+                    part_1: Some(Duration::from_millis(40).into()),
                     part_2: None,
                     total_nanos: 4e+10,
+                    failures: vec![],
+                    memory: None,
                 },
             ],
+            environment: None,
         }
     }
 
@@ -218,11 +568,68 @@ mod tests {
             assert_eq!(timings.data.len(), 1);
             let timing = timings.data.first().unwrap();
             assert_eq!(timing.day, day!(1));
-            assert_eq!(timing.part_1, Some("1ms".to_string()));
+            assert_eq!(timing.part_1.unwrap().nanos, 1_000_000);
+            assert_eq!(timing.part_1.unwrap().samples, None);
             assert_eq!(timing.part_2, None);
             assert_eq!(timing.total_nanos, 1_000_000_000_f64);
         }
 
+        #[test]
+        fn handles_parse_timings() {
+            let json = r#"{ "data": [{ "day": "01", "parse": "2ms", "part_1": "1ms", "part_2": null, "total_nanos": 3000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.parse.unwrap().nanos, 2_000_000);
+            assert_eq!(timing.part_1.unwrap().nanos, 1_000_000);
+        }
+
+        #[test]
+        fn handles_numeric_timings() {
+            let json = r#"{ "version": 2, "data": [{ "day": "01", "parse": null, "part_1": { "nanos": 1500, "samples": 100 }, "part_2": null, "total_nanos": 1500 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.parse, None);
+            assert_eq!(timing.part_1.unwrap().nanos, 1500);
+            assert_eq!(timing.part_1.unwrap().samples, Some(100));
+        }
+
+        #[test]
+        #[should_panic]
+        fn panics_for_newer_versions() {
+            let json = r#"{ "version": 3, "data": [] }"#.to_string();
+            Timings::try_from(json).unwrap();
+        }
+
+        #[test]
+        fn handles_failures() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": null, "part_2": null, "total_nanos": 0, "failures": [{ "part": 1, "reason": "timed out" }] }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.failures.len(), 1);
+            assert_eq!(timing.failures[0].part, 1);
+            assert_eq!(timing.failures[0].reason, "timed out");
+        }
+
+        #[test]
+        fn handles_memory() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000, "memory": { "allocations": 4, "bytes": 2048, "peak": 1024 } }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let memory = timings.data.first().unwrap().memory.unwrap();
+            assert_eq!(memory.allocations, 4);
+            assert_eq!(memory.bytes, 2048);
+            assert_eq!(memory.peak, 1024);
+        }
+
+        #[test]
+        fn handles_environment() {
+            let json = r#"{ "data": [], "environment": { "cpu": "Ryzen", "cores": 16, "rustc": null, "target": null, "profile": "release", "rayon_threads": "4", "git_commit": null } }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let environment = timings.environment.unwrap();
+            assert_eq!(environment.cpu, Some("Ryzen".into()));
+            assert_eq!(environment.cores, Some(16));
+            assert_eq!(environment.rayon_threads, Some("4".into()));
+        }
+
         #[test]
         fn handles_empty_timings() {
             let json = r#"{ "data": [] }"#.to_string();
@@ -247,6 +654,7 @@ mod tests {
 
     mod serialization {
         use super::get_mock_timings;
+        use crate::template::{json, timings::Timings};
         use std::collections::HashMap;
         use tinyjson::JsonValue;
 
@@ -254,11 +662,10 @@ mod tests {
         fn serializes_timings() {
             let timings = get_mock_timings();
             let value = JsonValue::try_from(timings).unwrap();
+            let map = value.get::<HashMap<String, JsonValue>>().unwrap();
+            assert_eq!(map.get("version").unwrap().get::<f64>(), Some(&2.0));
             assert_eq!(
-                value
-                    .get::<HashMap<String, JsonValue>>()
-                    .unwrap()
-                    .get("data")
+                map.get("data")
                     .unwrap()
                     .get::<Vec<JsonValue>>()
                     .unwrap()
@@ -266,9 +673,21 @@ mod tests {
                 3
             );
         }
+
+        #[test]
+        fn roundtrips_timings() {
+            let timings = get_mock_timings();
+            let json = json::format_sorted(&JsonValue::from(timings.clone()));
+            let parsed = Timings::try_from(json).unwrap();
+            assert_eq!(parsed.data.len(), 3);
+            assert_eq!(parsed.data[0].part_1, timings.data[0].part_1);
+            assert_eq!(parsed.data[2].part_2, None);
+        }
     }
 
     mod is_day_complete {
+        use std::time::Duration;
+
         use crate::{
             day,
             template::timings::{Timing, Timings},
@@ -279,13 +698,17 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
-                    part_1: Some("1ms".into()),
-                    part_2: Some("2ms".into()),
+                    parse: None,
+                    part_1: Some(Duration::from_millis(1).into()),
+                    part_2: Some(Duration::from_millis(2).into()),
                     total_nanos: 3_000_000_000_f64,
+                    failures: vec![],
+                    memory: None,
                 }],
+                environment: None,
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -293,13 +716,17 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
-                    part_1: Some("1ms".into()),
+                    parse: None,
+                    part_1: Some(Duration::from_millis(1).into()),
                     part_2: None,
                     total_nanos: 1_000_000_000_f64,
+                    failures: vec![],
+                    memory: None,
                 }],
+                environment: None,
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -307,20 +734,27 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
+                    parse: None,
                     part_1: None,
                     part_2: None,
                     total_nanos: 0.0,
+                    failures: vec![],
+                    memory: None,
                 }],
+                environment: None,
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
     mod merge {
+        use std::str::FromStr;
+
         use crate::{
             day,
-            template::timings::{Timing, Timings},
+            template::environment::Environment,
+            template::timings::{Failure, MergeStrategy, PhaseTiming, Timing, Timings},
         };
 
         use super::get_mock_timings;
@@ -331,10 +765,14 @@ mod tests {
             let other = Timings {
                 data: vec![Timing {
                     day: day!(3),
+                    parse: None,
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    failures: vec![],
+                    memory: None,
                 }],
+                environment: None,
             };
             let merged = timings.merge(&other);
             assert_eq!(merged.data.len(), 4);
@@ -351,10 +789,14 @@ mod tests {
             let other = Timings {
                 data: vec![Timing {
                     day: day!(2),
+                    parse: None,
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    failures: vec![],
+                    memory: None,
                 }],
+                environment: None,
             };
             let merged = timings.merge(&other);
 
@@ -365,6 +807,33 @@ mod tests {
             assert_eq!(merged.data[2].day, day!(4));
         }
 
+        #[test]
+        fn keeps_the_environment_of_days_not_run_again() {
+            let environment = |cpu: &str| Environment {
+                cpu: Some(cpu.into()),
+                profile: "release".into(),
+                ..Environment::default()
+            };
+
+            let mut timings = get_mock_timings();
+            timings.environment = Some(environment("old"));
+
+            let mut partial = get_mock_timings();
+            partial.data.truncate(1);
+            partial.environment = Some(environment("new"));
+            assert_eq!(
+                timings.merge(&partial).environment,
+                Some(environment("old"))
+            );
+
+            let mut complete = get_mock_timings();
+            complete.environment = Some(environment("new"));
+            assert_eq!(
+                timings.merge(&complete).environment,
+                Some(environment("new"))
+            );
+        }
+
         #[test]
         fn handles_empty_timings() {
             let timings = Timings::default();
@@ -380,5 +849,100 @@ mod tests {
             let merged = timings.merge(&other);
             assert_eq!(merged.data.len(), 3);
         }
+
+        fn single(part_1: Option<u64>, part_2: Option<u64>) -> Timings {
+            let phase = |nanos: Option<u64>| {
+                nanos.map(|nanos| PhaseTiming {
+                    nanos,
+                    samples: None,
+                })
+            };
+            Timings {
+                data: vec![Timing {
+                    day: day!(1),
+                    parse: None,
+                    part_1: phase(part_1),
+                    part_2: phase(part_2),
+                    total_nanos: (part_1.unwrap_or(0) + part_2.unwrap_or(0)) as f64,
+                    failures: if part_2.is_none() {
+                        vec![Failure {
+                            part: 2,
+                            reason: "panicked".into(),
+                        }]
+                    } else {
+                        vec![]
+                    },
+                    memory: None,
+                }],
+                environment: None,
+            }
+        }
+
+        #[test]
+        fn parses_strategies() {
+            assert_eq!(MergeStrategy::from_str("latest"), Ok(MergeStrategy::Latest));
+            assert_eq!(MergeStrategy::from_str("best"), Ok(MergeStrategy::Best));
+            assert_eq!(
+                MergeStrategy::from_str("median"),
+                Ok(MergeStrategy::Median(5))
+            );
+            assert_eq!(
+                MergeStrategy::from_str("median:3"),
+                Ok(MergeStrategy::Median(3))
+            );
+            assert!(MergeStrategy::from_str("median:0").is_err());
+            assert!(MergeStrategy::from_str("fastest").is_err());
+        }
+
+        #[test]
+        fn merges_latest() {
+            let stored = single(Some(100), Some(200));
+            let merged = stored.merge_with(&single(Some(300), None), MergeStrategy::Latest, &[]);
+            assert_eq!(merged.data[0].part_1.unwrap().nanos, 300);
+            assert!(merged.data[0].part_2.is_none());
+        }
+
+        #[test]
+        fn merges_best() {
+            let stored = single(Some(100), Some(200));
+            let merged = stored.merge_with(&single(Some(300), Some(150)), MergeStrategy::Best, &[]);
+            assert_eq!(merged.data[0].part_1.unwrap().nanos, 100);
+            assert_eq!(merged.data[0].part_2.unwrap().nanos, 150);
+            assert_eq!(merged.data[0].total_nanos, 250_f64);
+
+            // a failing part keeps its stored timing.
+            let merged = stored.merge_with(&single(Some(50), None), MergeStrategy::Best, &[]);
+            assert_eq!(merged.data[0].part_1.unwrap().nanos, 50);
+            assert_eq!(merged.data[0].part_2.unwrap().nanos, 200);
+            assert!(merged.data[0].failures.is_empty());
+
+            // days without stored timings are added as is.
+            let merged = Timings::default().merge_with(&stored, MergeStrategy::Best, &[]);
+            assert_eq!(merged.data[0].part_1.unwrap().nanos, 100);
+        }
+
+        #[test]
+        fn merges_median() {
+            let history = [
+                single(Some(900), Some(10)),
+                single(Some(100), Some(20)),
+                single(Some(300), None),
+            ];
+            let stored = history.last().unwrap().clone();
+            let new = single(Some(200), Some(40));
+
+            let merged = stored.merge_with(&new, MergeStrategy::Median(3), &history);
+            // median of 100, 300, 200 and of 20, 40.
+            assert_eq!(merged.data[0].part_1.unwrap().nanos, 200);
+            assert_eq!(merged.data[0].part_2.unwrap().nanos, 20);
+
+            let merged = stored.merge_with(&new, MergeStrategy::Median(5), &history);
+            // median of 900, 100, 300, 200 and of 10, 20, 40.
+            assert_eq!(merged.data[0].part_1.unwrap().nanos, 200);
+            assert_eq!(merged.data[0].part_2.unwrap().nanos, 20);
+
+            let merged = stored.merge_with(&new, MergeStrategy::Median(1), &history);
+            assert_eq!(merged.data[0].part_1.unwrap().nanos, 200);
+        }
     }
 }